@@ -0,0 +1,59 @@
+//! Document-related utilities.
+
+use crate::{document, head, JsError};
+
+/// Injects `<style>` element with given `id` and `css` into document's head.
+///
+/// Idempotent - if `<style>` element with `id` already exists only its content
+/// is replaced with `css`. Returns error if `id` is taken by element of other type.
+pub fn inject_style(id: &str, css: &str) -> Result<(), JsError> {
+    let document = document();
+    match document.get_element_by_id(id) {
+        Some(style) if style.tag_name() == "STYLE" => style.set_text_content(Some(css)),
+        Some(element) => {
+            return Err(JsError::new(
+                format!("id {:?} is taken by <{}> element", id, element.local_name()).into(),
+            ))
+        }
+        None => {
+            let style = document.create_element("style")?;
+            style.set_id(id);
+            style.set_text_content(Some(css));
+            head().append_child(&style)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{body, document, document::inject_style, head};
+
+    #[wasm_bindgen_test]
+    fn test_inject_style() {
+        inject_style("test-style", "body { margin: 1px; }").unwrap();
+        inject_style("test-style", "body { margin: 2px; }").unwrap();
+
+        let styles = head().query_selector_all("#test-style").unwrap();
+        assert_eq!(styles.length(), 1);
+        let style = document().get_element_by_id("test-style").unwrap();
+        assert_eq!(style.tag_name(), "STYLE");
+        assert_eq!(style.text_content().unwrap(), "body { margin: 2px; }");
+        style.remove();
+    }
+
+    #[wasm_bindgen_test]
+    fn test_inject_style_id_collision() {
+        let div = document().create_element("div").unwrap();
+        div.set_id("colliding-style");
+        div.set_text_content(Some("content"));
+        body().append_child(&div).unwrap();
+
+        assert!(inject_style("colliding-style", "body { margin: 1px; }").is_err());
+        assert_eq!(div.text_content().unwrap(), "content");
+        assert!(head().query_selector("#colliding-style").unwrap().is_none());
+        div.remove();
+    }
+}
@@ -0,0 +1,167 @@
+//! Error types shared across the crate.
+
+use std::fmt::Display;
+
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Wrapper for [`JsValue`] errors implementing [`std::error::Error`].
+#[derive(Debug)]
+pub struct JsError {
+    value: JsValue,
+    source: Option<Box<dyn std::error::Error>>,
+}
+
+impl JsError {
+    /// Creates new error wrapping `value`.
+    pub fn new(value: JsValue) -> Self {
+        JsError {
+            value,
+            source: None,
+        }
+    }
+
+    /// Creates new error wrapping `value` caused by `source` error.
+    pub fn with_source(value: JsValue, source: impl std::error::Error + 'static) -> Self {
+        JsError {
+            value,
+            source: Some(Box::new(source)),
+        }
+    }
+
+    /// Returns wrapped JS value.
+    pub fn value(&self) -> &JsValue {
+        &self.value
+    }
+
+    /// Consumes error, returning wrapped JS value.
+    pub fn into_value(self) -> JsValue {
+        self.value
+    }
+}
+
+impl JsError {
+    /// Extracts `name`, `message` and `stack` of underlying JS `Error`.
+    ///
+    /// Values that aren't JS `Error`s are reported as `Error` with
+    /// their debug representation as message.
+    pub fn to_report(&self) -> ErrorReport {
+        match self.value.dyn_ref::<js_sys::Error>() {
+            Some(error) => ErrorReport {
+                name: error.name().into(),
+                message: error.message().into(),
+                stack: js_sys::Reflect::get(error, &JsValue::from_str("stack"))
+                    .ok()
+                    .and_then(|stack| stack.as_string()),
+            },
+            None => ErrorReport {
+                name: "Error".to_string(),
+                message: self
+                    .value
+                    .as_string()
+                    .unwrap_or_else(|| format!("{:?}", self.value)),
+                stack: None,
+            },
+        }
+    }
+}
+
+impl Display for JsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl std::error::Error for JsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_deref()
+    }
+}
+
+impl From<JsValue> for JsError {
+    fn from(value: JsValue) -> Self {
+        JsError::new(value)
+    }
+}
+
+/// Error details suitable for reporting (e.g. sending to backend).
+///
+/// Serializable with `serde` feature enabled.
+///
+/// See [`JsError::to_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorReport {
+    /// Error name (e.g. `TypeError`).
+    pub name: String,
+    /// Error message.
+    pub message: String,
+    /// Stack trace, if available.
+    pub stack: Option<String>,
+}
+
+/// Error returned when a deadline has elapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(pub(crate) ());
+
+impl Display for Elapsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Error returned when an operation was aborted before completing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aborted(pub(crate) ());
+
+impl Display for Aborted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation was aborted")
+    }
+}
+
+impl std::error::Error for Aborted {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::error::{Aborted, Elapsed, JsError};
+
+    #[wasm_bindgen_test]
+    fn test_error_source() {
+        let error = JsError::from(JsValue::from("outer"));
+        assert!(error.source().is_none());
+
+        let error =
+            JsError::with_source(JsValue::from("outer"), JsError::new(JsValue::from("inner")));
+        let source = error.source().unwrap().downcast_ref::<JsError>().unwrap();
+        assert_eq!(source.value(), &JsValue::from("inner"));
+        assert_eq!(error.into_value(), JsValue::from("outer"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_to_report() {
+        let error = js_sys::Error::new("something failed");
+        error.set_name("TypeError");
+        let report = JsError::new(error.into()).to_report();
+        assert_eq!(report.name, "TypeError");
+        assert_eq!(report.message, "something failed");
+        assert!(report.stack.is_some());
+
+        let report = JsError::new(JsValue::from("plain")).to_report();
+        assert_eq!(report.name, "Error");
+        assert_eq!(report.message, "plain");
+        assert_eq!(report.stack, None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_display() {
+        assert_eq!(Elapsed(()).to_string(), "deadline has elapsed");
+        assert_eq!(Aborted(()).to_string(), "operation was aborted");
+    }
+}
@@ -1,18 +1,32 @@
 //! Event-related utilities.
 
 use std::{
+    any::Any,
     cell::RefCell,
     collections::VecDeque,
+    fmt::{self, Debug},
     pin::Pin,
-    rc::Rc,
+    rc::{Rc, Weak},
     task::{Context, Poll, Waker},
 };
 
-use futures::stream::FusedStream;
-use wasm_bindgen::{convert::FromWasmAbi, prelude::Closure, JsCast};
-use web_sys::EventTarget;
+use futures::{future::FusedFuture, stream::FusedStream, Future};
+use wasm_bindgen::{convert::FromWasmAbi, prelude::Closure, JsCast, JsValue};
+use web_sys::{
+    AddEventListenerOptions, Document, Element, Event, EventTarget, MouseEvent, Node, PointerEvent,
+};
 
-use crate::{closure, JsError};
+use crate::{closure, document, window, window::FrameRequest, JsError};
+#[cfg(feature = "sleep")]
+use crate::{error::Elapsed, sleep::sleep};
+#[cfg(feature = "sleep")]
+use futures::future::{select, Either};
+#[cfg(feature = "sleep")]
+use futures::StreamExt;
+#[cfg(feature = "sleep")]
+use std::time::Duration;
+#[cfg(feature = "sleep")]
+use web_sys::TransitionEvent;
 
 /// Trait for listening to events with a callback.
 pub trait When: AsRef<EventTarget> + Sized {
@@ -31,11 +45,204 @@ pub trait Stream: When {
         self: &Rc<Self>,
         event_type: &'static str,
     ) -> Result<EventStream<Self, E>, JsError>;
+
+    /// Create stream of given event type yielding at most one event per animation frame.
+    ///
+    /// Events received between animation frames are coalesced - only the latest
+    /// one is yielded. Useful for high-frequency events like `pointermove`.
+    fn listen_raf_throttled<E: FromWasmAbi + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<EventStream<Self, E>, JsError>;
+
+    /// Create stream of given event type pairing each event with its
+    /// [`timeStamp`](https://developer.mozilla.org/en-US/docs/Web/API/Event/timeStamp)
+    /// (in milliseconds).
+    ///
+    /// Useful for recognizing timing-based gestures like double-tap or long-press.
+    fn listen_timed<E: FromWasmAbi + AsRef<Event> + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<EventStream<Self, (E, f64), E>, JsError>;
+
+    /// Create stream of given event type, listening in the capture phase.
+    ///
+    /// Useful for delegating events that don't bubble (like `focus` and `blur`)
+    /// to an ancestor element.
+    fn listen_capturing<E: FromWasmAbi + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<EventStream<Self, E>, JsError>;
+}
+
+/// Trait for awaiting a single event.
+pub trait Once: When {
+    /// Create future resolving with the next event of given type.
+    ///
+    /// Listener is registered with the native `once` option, so the browser
+    /// removes it as soon as the first event is dispatched.
+    fn once<E: FromWasmAbi + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<NextEvent<Self, E>, JsError>;
+}
+
+/// Creates [`EventStream`](crate::event::EventStream) of given event type with event's type inferred
+/// from its name.
+///
+/// Event name has to be a string literal. Common event names are mapped to
+/// their DOM event types (`"click"` to [`MouseEvent`], `"keydown"` to
+/// [`KeyboardEvent`] and so on), unknown names fall back to [`Event`].
+///
+/// ```ignore
+/// let clicks = on_event!(&body, "click")?; // EventStream<_, MouseEvent>
+/// ```
+///
+/// [`MouseEvent`]: web_sys::MouseEvent
+/// [`KeyboardEvent`]: web_sys::KeyboardEvent
+/// [`Event`]: web_sys::Event
+#[macro_export]
+macro_rules! on_event {
+    ($target:expr, $event_type:tt) => {
+        $crate::event::Stream::listen::<$crate::event_type!($event_type)>($target, $event_type)
+    };
+}
+
+/// Maps event name (string literal) to its DOM event type.
+///
+/// See [`on_event`](crate::on_event).
+#[macro_export]
+macro_rules! event_type {
+    ("click") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("dblclick") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mousedown") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mouseup") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mousemove") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mouseenter") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mouseleave") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mouseover") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("mouseout") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("contextmenu") => {
+        $crate::event::__web_sys::MouseEvent
+    };
+    ("keydown") => {
+        $crate::event::__web_sys::KeyboardEvent
+    };
+    ("keyup") => {
+        $crate::event::__web_sys::KeyboardEvent
+    };
+    ("keypress") => {
+        $crate::event::__web_sys::KeyboardEvent
+    };
+    ("pointerdown") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointerup") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointermove") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointerenter") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointerleave") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointerover") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointerout") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("pointercancel") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("gotpointercapture") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("lostpointercapture") => {
+        $crate::event::__web_sys::PointerEvent
+    };
+    ("focus") => {
+        $crate::event::__web_sys::FocusEvent
+    };
+    ("blur") => {
+        $crate::event::__web_sys::FocusEvent
+    };
+    ("focusin") => {
+        $crate::event::__web_sys::FocusEvent
+    };
+    ("focusout") => {
+        $crate::event::__web_sys::FocusEvent
+    };
+    ("input") => {
+        $crate::event::__web_sys::InputEvent
+    };
+    ("beforeinput") => {
+        $crate::event::__web_sys::InputEvent
+    };
+    ("wheel") => {
+        $crate::event::__web_sys::WheelEvent
+    };
+    ("touchstart") => {
+        $crate::event::__web_sys::TouchEvent
+    };
+    ("touchend") => {
+        $crate::event::__web_sys::TouchEvent
+    };
+    ("touchmove") => {
+        $crate::event::__web_sys::TouchEvent
+    };
+    ("touchcancel") => {
+        $crate::event::__web_sys::TouchEvent
+    };
+    ("hashchange") => {
+        $crate::event::__web_sys::HashChangeEvent
+    };
+    ("transitionrun") => {
+        $crate::event::__web_sys::TransitionEvent
+    };
+    ("transitionstart") => {
+        $crate::event::__web_sys::TransitionEvent
+    };
+    ("transitionend") => {
+        $crate::event::__web_sys::TransitionEvent
+    };
+    ("transitioncancel") => {
+        $crate::event::__web_sys::TransitionEvent
+    };
+    ($other:literal) => {
+        $crate::event::__web_sys::Event
+    };
 }
 
+#[doc(hidden)]
+pub use web_sys as __web_sys;
+
 /// Listener of events.
 ///
-/// Drop to remove event listener.
+/// Drop or call [`remove`] to remove event listener.
+///
+/// [`remove`]: EventListener::remove
 #[derive(Debug)]
 pub struct EventListener<T, E>
 where
@@ -44,6 +251,39 @@ where
     event_type: &'static str,
     target: Rc<T>,
     closure: Closure<dyn FnMut(E)>,
+    capture: bool,
+    active: bool,
+}
+
+impl<T, E> EventListener<T, E>
+where
+    T: AsRef<EventTarget>,
+{
+    /// Removes event listener right away.
+    ///
+    /// Unlike dropping it allows keeping the (now inactive) listener around,
+    /// for example in a struct field. Calling it again has no effect.
+    pub fn remove(&mut self) {
+        if self.active {
+            self.active = false;
+            let _ = self
+                .target
+                .as_ref()
+                .as_ref()
+                .remove_event_listener_with_callback_and_bool(
+                    self.event_type,
+                    self.closure.as_ref().unchecked_ref(),
+                    self.capture,
+                );
+        }
+    }
+
+    /// Returns `true` if listener wasn't [removed] yet.
+    ///
+    /// [removed]: EventListener::remove
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
 }
 
 impl<T, E> Drop for EventListener<T, E>
@@ -51,14 +291,7 @@ where
     T: AsRef<EventTarget>,
 {
     fn drop(&mut self) {
-        let _ = self
-            .target
-            .as_ref()
-            .as_ref()
-            .remove_event_listener_with_callback(
-                self.event_type,
-                self.closure.as_ref().unchecked_ref(),
-            );
+        self.remove();
     }
 }
 
@@ -71,29 +304,61 @@ where
         event_type: &'static str,
         callback: F,
     ) -> Result<EventListener<Self, E>, JsError> {
-        let closure = closure!(callback);
-        self.as_ref()
-            .as_ref()
-            .add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())?;
-        Ok(EventListener {
+        add_event_listener(
+            self,
             event_type,
-            target: self.clone(),
-            closure,
-        })
+            callback,
+            false,
+            &AddEventListenerOptions::new(),
+        )
     }
 }
 
+fn add_event_listener<T, E, F>(
+    target: &Rc<T>,
+    event_type: &'static str,
+    callback: F,
+    capture: bool,
+    options: &AddEventListenerOptions,
+) -> Result<EventListener<T, E>, JsError>
+where
+    T: AsRef<EventTarget>,
+    E: FromWasmAbi + 'static,
+    F: FnMut(E) + 'static,
+{
+    options.set_capture(capture);
+    let closure = closure!(callback);
+    target
+        .as_ref()
+        .as_ref()
+        .add_event_listener_with_callback_and_add_event_listener_options(
+            event_type,
+            closure.as_ref().unchecked_ref(),
+            options,
+        )?;
+    Ok(EventListener {
+        event_type,
+        target: target.clone(),
+        closure,
+        capture,
+        active: true,
+    })
+}
+
 /// Stream of events.
+///
+/// `L` is the type of events received by the underlying listener,
+/// in most cases it's the same as type of stream's items (`E`).
 #[derive(Debug)]
-pub struct EventStream<T, E>
+pub struct EventStream<T, E, L = E>
 where
     T: When,
 {
     state: Rc<RefCell<State<E>>>,
-    listener: Option<EventListener<T, E>>,
+    listener: Option<EventListener<T, L>>,
 }
 
-impl<T, E> EventStream<T, E>
+impl<T, E, L> EventStream<T, E, L>
 where
     T: AsRef<EventTarget>,
 {
@@ -114,9 +379,9 @@ struct State<E> {
     waker: Option<Waker>,
 }
 
-impl<T, E> Unpin for EventStream<T, E> where T: AsRef<EventTarget> {}
+impl<T, E, L> Unpin for EventStream<T, E, L> where T: AsRef<EventTarget> {}
 
-impl<T, E> futures::Stream for EventStream<T, E>
+impl<T, E, L> futures::Stream for EventStream<T, E, L>
 where
     T: AsRef<EventTarget>,
 {
@@ -142,7 +407,7 @@ where
     }
 }
 
-impl<T, E> FusedStream for EventStream<T, E>
+impl<T, E, L> FusedStream for EventStream<T, E, L>
 where
     T: AsRef<EventTarget>,
 {
@@ -159,40 +424,724 @@ where
         self: &Rc<Self>,
         event_type: &'static str,
     ) -> Result<EventStream<Self, E>, JsError> {
-        let state = Rc::new(RefCell::new(State {
-            queue: VecDeque::new(),
-            waker: None,
-        }));
-        let state_clone = state.clone();
-        let listener = self.when(event_type, move |event| {
-            let mut state = state_clone.borrow_mut();
-            state.queue.push_back(event);
+        listen_filter_map(self, event_type, Some)
+    }
+
+    fn listen_raf_throttled<E: FromWasmAbi + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<EventStream<Self, E>, JsError> {
+        listen_raf_throttled_filter_map(self, event_type, Some)
+    }
+
+    fn listen_capturing<E: FromWasmAbi + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<EventStream<Self, E>, JsError> {
+        listen_filter_map_with_capture(self, event_type, Some, true)
+    }
+
+    fn listen_timed<E: FromWasmAbi + AsRef<Event> + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<EventStream<Self, (E, f64), E>, JsError> {
+        listen_filter_map(self, event_type, |event: E| {
+            let time_stamp = event.as_ref().time_stamp();
+            Some((event, time_stamp))
+        })
+    }
+}
+
+/// Create stream of given event type with events transformed by `f`.
+///
+/// Events for which `f` returns `None` are skipped.
+pub(crate) fn listen_filter_map<T, L, E, F>(
+    target: &Rc<T>,
+    event_type: &'static str,
+    f: F,
+) -> Result<EventStream<T, E, L>, JsError>
+where
+    T: When,
+    L: FromWasmAbi + 'static,
+    E: 'static,
+    F: FnMut(L) -> Option<E> + 'static,
+{
+    listen_filter_map_with_capture(target, event_type, f, false)
+}
+
+fn listen_filter_map_with_capture<T, L, E, F>(
+    target: &Rc<T>,
+    event_type: &'static str,
+    mut f: F,
+    capture: bool,
+) -> Result<EventStream<T, E, L>, JsError>
+where
+    T: When,
+    L: FromWasmAbi + 'static,
+    E: 'static,
+    F: FnMut(L) -> Option<E> + 'static,
+{
+    let state = Rc::new(RefCell::new(State {
+        queue: VecDeque::new(),
+        waker: None,
+    }));
+    let state_clone = state.clone();
+    let listener = add_event_listener(
+        target,
+        event_type,
+        move |event| {
+            if let Some(item) = f(event) {
+                let mut state = state_clone.borrow_mut();
+                state.queue.push_back(item);
+                if let Some(waker) = &state.waker {
+                    waker.wake_by_ref();
+                }
+            }
+        },
+        capture,
+        &AddEventListenerOptions::new(),
+    )?;
+    let event_stream = EventStream {
+        state,
+        listener: Some(listener),
+    };
+    Ok(event_stream)
+}
+
+/// Create stream of given event type yielding at most one event per animation
+/// frame, with events transformed by `f`.
+///
+/// `f` is applied to the latest event at the time of animation frame.
+pub(crate) fn listen_raf_throttled_filter_map<T, L, E, F>(
+    target: &Rc<T>,
+    event_type: &'static str,
+    f: F,
+) -> Result<EventStream<T, E, L>, JsError>
+where
+    T: When,
+    L: FromWasmAbi + 'static,
+    E: 'static,
+    F: FnMut(L) -> Option<E> + 'static,
+{
+    let state = Rc::new(RefCell::new(State {
+        queue: VecDeque::new(),
+        waker: None,
+    }));
+    let frame = Rc::new_cyclic(|frame: &Weak<RefCell<Frame<L, E>>>| {
+        let frame = frame.clone();
+        RefCell::new(Frame {
+            latest: None,
+            state: state.clone(),
+            map: Box::new(f),
+            request: FrameRequest::new(move |_| {
+                if let Some(frame) = frame.upgrade() {
+                    frame.borrow_mut().flush();
+                }
+            }),
+        })
+    });
+    let listener = target.when(event_type, move |event| {
+        let mut frame = frame.borrow_mut();
+        frame.latest = Some(event);
+        frame.request.request();
+    })?;
+    let event_stream = EventStream {
+        state,
+        listener: Some(listener),
+    };
+    Ok(event_stream)
+}
+
+/// Latest event waiting for the next animation frame.
+struct Frame<L, E> {
+    latest: Option<L>,
+    state: Rc<RefCell<State<E>>>,
+    map: Box<dyn FnMut(L) -> Option<E>>,
+    request: FrameRequest,
+}
+
+impl<L, E> Frame<L, E> {
+    /// Moves latest event (if any) into the stream's queue.
+    fn flush(&mut self) {
+        if let Some(item) = self.latest.take().and_then(&mut self.map) {
+            let mut state = self.state.borrow_mut();
+            state.queue.clear();
+            state.queue.push_back(item);
             if let Some(waker) = &state.waker {
                 waker.wake_by_ref();
             }
-        })?;
-        let event_stream = EventStream {
+        }
+    }
+}
+
+impl<L, E> Drop for Frame<L, E> {
+    fn drop(&mut self) {
+        // Listener is gone (e.g. stream was stopped) - event received
+        // before that still has to be yielded.
+        self.flush();
+    }
+}
+
+/// Future returned by [`Once::once`], resolving with the next event.
+#[derive(Debug)]
+pub struct NextEvent<T, E>
+where
+    T: AsRef<EventTarget>,
+{
+    state: Rc<RefCell<NextEventState<E>>>,
+    listener: Option<EventListener<T, E>>,
+}
+
+#[derive(Debug)]
+struct NextEventState<E> {
+    event: Option<E>,
+    waker: Option<Waker>,
+}
+
+impl<T, E> Unpin for NextEvent<T, E> where T: AsRef<EventTarget> {}
+
+impl<T, E> Future for NextEvent<T, E>
+where
+    T: AsRef<EventTarget>,
+{
+    type Output = E;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.listener.is_none() {
+            return Poll::Pending;
+        }
+        let mut state = self.state.borrow_mut();
+        if let Some(event) = state.event.take() {
+            drop(state);
+            self.listener = None;
+            Poll::Ready(event)
+        } else {
+            let new_waker = cx.waker();
+            if let Some(waker) = &mut state.waker {
+                if !waker.will_wake(new_waker) {
+                    state.waker = Some(new_waker.clone());
+                }
+            } else {
+                state.waker = Some(new_waker.clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, E> FusedFuture for NextEvent<T, E>
+where
+    T: AsRef<EventTarget>,
+{
+    fn is_terminated(&self) -> bool {
+        self.listener.is_none()
+    }
+}
+
+impl<T> Once for T
+where
+    T: When,
+{
+    fn once<E: FromWasmAbi + 'static>(
+        self: &Rc<Self>,
+        event_type: &'static str,
+    ) -> Result<NextEvent<Self, E>, JsError> {
+        let state = Rc::new(RefCell::new(NextEventState {
+            event: None,
+            waker: None,
+        }));
+        let state_clone = state.clone();
+        let options = AddEventListenerOptions::new();
+        options.set_once(true);
+        let listener = add_event_listener(
+            self,
+            event_type,
+            move |event| {
+                let mut state = state_clone.borrow_mut();
+                state.event = Some(event);
+                if let Some(waker) = &state.waker {
+                    waker.wake_by_ref();
+                }
+            },
+            false,
+            &options,
+        )?;
+        Ok(NextEvent {
             state,
             listener: Some(listener),
-        };
-        Ok(event_stream)
+        })
+    }
+}
+
+/// Waits for the next event of given type, giving up after `duration`.
+///
+/// Listener is removed both when the event arrives and when the timeout elapses.
+#[cfg(feature = "sleep")]
+pub fn when_or_timeout<T, E>(
+    target: &Rc<T>,
+    event_type: &'static str,
+    duration: Duration,
+) -> Result<impl Future<Output = Result<E, Elapsed>>, JsError>
+where
+    T: When,
+    E: FromWasmAbi + 'static,
+{
+    let next = target.once(event_type)?;
+    let sleep = sleep(duration);
+    Ok(async move {
+        match select(next, sleep).await {
+            Either::Left((event, _)) => Ok(event),
+            Either::Right(_) => Err(Elapsed(())),
+        }
+    })
+}
+
+/// Waits for `element`'s CSS transition to end.
+///
+/// If `property` is given only transition of that property is awaited.
+/// Since `transitionend` never fires for interrupted transitions, future
+/// also resolves once the longest transition declared in `element`'s
+/// computed style should have ended.
+#[cfg(feature = "sleep")]
+pub fn on_transition_end<T>(
+    element: &Rc<T>,
+    property: Option<&str>,
+) -> Result<impl Future<Output = ()>, JsError>
+where
+    T: AsRef<Element> + AsRef<EventTarget>,
+{
+    let element_ref: &Element = (**element).as_ref();
+    let fallback = transition_fallback(element_ref)?;
+    let target = JsValue::from(element_ref.clone());
+    let property = property.map(str::to_string);
+    let mut events = listen_filter_map(element, "transitionend", move |event: TransitionEvent| {
+        let own = event
+            .target()
+            .is_some_and(|event_target| JsValue::from(event_target) == target);
+        let matching = property
+            .as_ref()
+            .is_none_or(|property| event.property_name() == *property);
+        (own && matching).then_some(())
+    })?;
+    let sleep = sleep(fallback);
+    Ok(async move {
+        select(events.next(), sleep).await;
+    })
+}
+
+/// Extra time given to transitions before fallback kicks in.
+#[cfg(feature = "sleep")]
+const TRANSITION_FALLBACK_MARGIN: Duration = Duration::from_millis(100);
+
+#[cfg(feature = "sleep")]
+fn transition_fallback(element: &Element) -> Result<Duration, JsError> {
+    let style = window()
+        .get_computed_style(element)?
+        .ok_or_else(|| JsError::new("element has no computed style".into()))?;
+    let durations = parse_times(&style.get_property_value("transition-duration")?);
+    let delays = parse_times(&style.get_property_value("transition-delay")?);
+    let longest = durations
+        .iter()
+        .enumerate()
+        .map(|(index, duration)| {
+            let delay = delays
+                .get(index % delays.len().max(1))
+                .copied()
+                .unwrap_or_default();
+            *duration + delay
+        })
+        .max()
+        .unwrap_or_default();
+    Ok(longest + TRANSITION_FALLBACK_MARGIN)
+}
+
+/// Parses comma-separated list of CSS times (like `"0.3s, 150ms"`).
+///
+/// Invalid and negative entries are treated as zero.
+#[cfg(feature = "sleep")]
+fn parse_times(value: &str) -> Vec<Duration> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|time| !time.is_empty())
+        .map(|time| {
+            let seconds = if let Some(milliseconds) = time.strip_suffix("ms") {
+                milliseconds.parse::<f64>().map(|value| value / 1000.0)
+            } else {
+                time.trim_end_matches('s').parse::<f64>()
+            };
+            Duration::try_from_secs_f64(seconds.unwrap_or_default()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Shared cell holding the most recent event, see [`capture_latest`].
+pub type Latest<E> = Rc<RefCell<Option<E>>>;
+
+/// Listen to given event type storing the most recent event in shared cell.
+///
+/// Returns listener together with the cell.
+pub fn capture_latest<T, E>(
+    target: &Rc<T>,
+    event_type: &'static str,
+) -> Result<(EventListener<T, E>, Latest<E>), JsError>
+where
+    T: When,
+    E: FromWasmAbi + 'static,
+{
+    let latest = Rc::new(RefCell::new(None));
+    let latest_clone = latest.clone();
+    let listener = target.when(event_type, move |event| {
+        *latest_clone.borrow_mut() = Some(event);
+    })?;
+    Ok((listener, latest))
+}
+
+/// Set of `window` and `document` listeners removed all at once on drop.
+///
+/// Useful for dismissible overlays (modals, dropdowns) that need global
+/// listeners only while open.
+#[derive(Default)]
+pub struct GlobalListeners {
+    listeners: Vec<Box<dyn Any>>,
+}
+
+impl GlobalListeners {
+    /// Creates new empty set of listeners.
+    pub fn new() -> Self {
+        GlobalListeners::default()
+    }
+
+    /// Adds `window` listener of given event type.
+    pub fn on_window<E: FromWasmAbi + 'static, F: FnMut(E) + 'static>(
+        self,
+        event_type: &'static str,
+        callback: F,
+    ) -> Result<Self, JsError> {
+        let listener = Rc::new(window()).when(event_type, callback)?;
+        Ok(self.with(listener))
+    }
+
+    /// Adds `document` listener of given event type.
+    pub fn on_document<E: FromWasmAbi + 'static, F: FnMut(E) + 'static>(
+        self,
+        event_type: &'static str,
+        callback: F,
+    ) -> Result<Self, JsError> {
+        let listener = Rc::new(document()).when(event_type, callback)?;
+        Ok(self.with(listener))
+    }
+
+    /// Adds already registered `listener` to the set.
+    pub fn with<T, E>(mut self, listener: EventListener<T, E>) -> Self
+    where
+        T: AsRef<EventTarget> + 'static,
+        E: 'static,
+    {
+        self.listeners.push(Box::new(listener));
+        self
+    }
+
+    /// Returns count of listeners in the set.
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    /// Returns `true` if set contains no listeners.
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+}
+
+impl Debug for GlobalListeners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalListeners")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Run `callback` when click lands outside of `element`.
+///
+/// Click that was being dispatched while listener was registered
+/// (e.g. the one that opened an overlay) is ignored.
+pub fn on_click_outside<T, F>(
+    element: &T,
+    mut callback: F,
+) -> Result<EventListener<Document, MouseEvent>, JsError>
+where
+    T: AsRef<Node>,
+    F: FnMut(MouseEvent) + 'static,
+{
+    let node: Node = element.as_ref().clone();
+    // `undefined` unless registered from within an event listener
+    let opening = window().event();
+    Rc::new(document()).when("click", move |event: MouseEvent| {
+        let value: &JsValue = event.as_ref();
+        if *value == opening {
+            return;
+        }
+        let inside = event
+            .target()
+            .and_then(|target| target.dyn_into::<Node>().ok())
+            .is_some_and(|target| node.contains(Some(&target)));
+        if !inside {
+            callback(event);
+        }
+    })
+}
+
+/// Captures pointer with given id, so `element` keeps receiving pointer events
+/// even when pointer leaves it.
+pub fn set_pointer_capture(element: &Element, pointer_id: i32) -> Result<(), JsError> {
+    Ok(element.set_pointer_capture(pointer_id)?)
+}
+
+/// Releases pointer capture set with [`set_pointer_capture`].
+pub fn release_pointer_capture(element: &Element, pointer_id: i32) -> Result<(), JsError> {
+    Ok(element.release_pointer_capture(pointer_id)?)
+}
+
+/// Create stream of `pointermove` events of a single drag over `element`.
+///
+/// Pointer is captured on `pointerdown`, all its `pointermove` events are
+/// streamed and stream terminates (releasing the capture) on `pointerup`
+/// or `pointercancel`.
+pub fn drag_stream<T>(element: &Rc<T>) -> Result<DragStream<T>, JsError>
+where
+    T: AsRef<Element> + AsRef<EventTarget>,
+{
+    let element_ref: &Element = (**element).as_ref();
+    let state = Rc::new(RefCell::new(DragState {
+        queue: VecDeque::new(),
+        waker: None,
+        pointer: None,
+        ended: false,
+    }));
+
+    let (state_clone, target) = (state.clone(), element_ref.clone());
+    let down = element.when("pointerdown", move |event: PointerEvent| {
+        let mut state = state_clone.borrow_mut();
+        if state.pointer.is_none() && !state.ended {
+            let _ = set_pointer_capture(&target, event.pointer_id());
+            state.pointer = Some(event.pointer_id());
+        }
+    })?;
+    let state_clone = state.clone();
+    let moves = element.when("pointermove", move |event: PointerEvent| {
+        let mut state = state_clone.borrow_mut();
+        if !state.ended && state.pointer == Some(event.pointer_id()) {
+            state.queue.push_back(event);
+            state.wake();
+        }
+    })?;
+    let mut listeners = vec![down, moves];
+    for event_type in ["pointerup", "pointercancel"] {
+        let (state_clone, target) = (state.clone(), element_ref.clone());
+        listeners.push(element.when(event_type, move |event: PointerEvent| {
+            let mut state = state_clone.borrow_mut();
+            if !state.ended && state.pointer == Some(event.pointer_id()) {
+                let _ = release_pointer_capture(&target, event.pointer_id());
+                state.ended = true;
+                state.wake();
+            }
+        })?);
+    }
+
+    Ok(DragStream {
+        state,
+        listeners,
+        element: element_ref.clone(),
+    })
+}
+
+/// Stream of `pointermove` events of a single drag.
+///
+/// This `struct` is created by the [`drag_stream`] function.
+#[derive(Debug)]
+pub struct DragStream<T>
+where
+    T: AsRef<EventTarget>,
+{
+    state: Rc<RefCell<DragState>>,
+    listeners: Vec<EventListener<T, PointerEvent>>,
+    element: Element,
+}
+
+#[derive(Debug)]
+struct DragState {
+    queue: VecDeque<PointerEvent>,
+    waker: Option<Waker>,
+    pointer: Option<i32>,
+    ended: bool,
+}
+
+impl DragState {
+    fn wake(&self) {
+        if let Some(waker) = &self.waker {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+impl<T> Drop for DragStream<T>
+where
+    T: AsRef<EventTarget>,
+{
+    fn drop(&mut self) {
+        let state = self.state.borrow();
+        if let (Some(pointer), false) = (state.pointer, state.ended) {
+            let _ = release_pointer_capture(&self.element, pointer);
+        }
+    }
+}
+
+impl<T> Unpin for DragStream<T> where T: AsRef<EventTarget> {}
+
+impl<T> futures::Stream for DragStream<T>
+where
+    T: AsRef<EventTarget>,
+{
+    type Item = PointerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.borrow_mut();
+        if let Some(event) = state.queue.pop_front() {
+            Poll::Ready(Some(event))
+        } else if state.ended {
+            drop(state);
+            self.listeners.clear();
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> FusedStream for DragStream<T>
+where
+    T: AsRef<EventTarget>,
+{
+    fn is_terminated(&self) -> bool {
+        let state = self.state.borrow();
+        state.ended && state.queue.is_empty()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{cell::Cell, rc::Rc, time::Duration};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        time::Duration,
+    };
 
-    use futures::StreamExt;
+    use futures::{
+        future::{select, Either, FusedFuture},
+        FutureExt, StreamExt,
+    };
+    use wasm_bindgen::{JsCast, JsValue};
     use wasm_bindgen_test::wasm_bindgen_test;
-    use web_sys::MouseEvent;
+    use web_sys::{
+        CustomEvent, CustomEventInit, Event, FocusEvent, HtmlElement, MouseEvent, PointerEvent,
+        PointerEventInit, TransitionEvent, TransitionEventInit,
+    };
 
     use crate::{
-        body,
-        event::{EventStream, Stream, When},
-        sleep, spawn,
+        body, document,
+        event::{
+            capture_latest, drag_stream, on_click_outside, on_transition_end, parse_times,
+            when_or_timeout, EventStream, GlobalListeners, Once, Stream, When,
+        },
+        sleep, spawn, window,
     };
 
+    #[wasm_bindgen_test]
+    async fn test_on_event() {
+        let body = Rc::new(body());
+
+        let mut clicks = on_event!(&body, "click").unwrap();
+        body.click();
+        let event: MouseEvent = clicks.next().await.unwrap();
+        assert_eq!(event.button(), 0);
+
+        let mut custom = on_event!(&body, "custom").unwrap();
+        body.dispatch_event(&web_sys::Event::new("custom").unwrap())
+            .unwrap();
+        let event: web_sys::Event = custom.next().await.unwrap();
+        assert_eq!(event.type_(), "custom");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_on_click_outside() {
+        let element = document().create_element("div").unwrap();
+        let element = element.dyn_into::<HtmlElement>().unwrap();
+        body().append_child(&element).unwrap();
+
+        let count = Rc::new(Cell::new(0));
+        let count_clone = count.clone();
+        let _listener =
+            on_click_outside(&element, move |_| count_clone.set(count_clone.get() + 1)).unwrap();
+        element.click();
+        assert_eq!(count.get(), 0);
+        body().click();
+        assert_eq!(count.get(), 1);
+        element.remove();
+    }
+
+    #[wasm_bindgen_test]
+    fn test_on_click_outside_opening_click() {
+        let body = Rc::new(body());
+        let element = document().create_element("div").unwrap();
+        let count = Rc::new(Cell::new(0));
+        let overlay = Rc::new(RefCell::new(None));
+
+        let (count_clone, overlay_clone) = (count.clone(), overlay.clone());
+        let opener = body
+            .when("click", move |_: MouseEvent| {
+                if overlay_clone.borrow().is_none() {
+                    let count_clone = count_clone.clone();
+                    let listeners = GlobalListeners::new().with(
+                        on_click_outside(&element, move |_| count_clone.set(count_clone.get() + 1))
+                            .unwrap(),
+                    );
+                    *overlay_clone.borrow_mut() = Some(listeners);
+                }
+            })
+            .unwrap();
+        body.click();
+        assert_eq!(overlay.borrow().as_ref().unwrap().len(), 1);
+        assert_eq!(count.get(), 0);
+        drop(opener);
+
+        body.click();
+        assert_eq!(count.get(), 1);
+        overlay.borrow_mut().take();
+        body.click();
+        assert_eq!(count.get(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_global_listeners() {
+        let count = Rc::new(Cell::new(0));
+        let count_clone = count.clone();
+        let listeners = GlobalListeners::new()
+            .on_document("custom", move |_: Event| {
+                count_clone.set(count_clone.get() + 1)
+            })
+            .unwrap();
+        document()
+            .dispatch_event(&Event::new("custom").unwrap())
+            .unwrap();
+        assert_eq!(count.get(), 1);
+        drop(listeners);
+        document()
+            .dispatch_event(&Event::new("custom").unwrap())
+            .unwrap();
+        assert_eq!(count.get(), 1);
+    }
+
     #[wasm_bindgen_test]
     async fn test_event_listener() {
         let body = Rc::new(body());
@@ -210,6 +1159,104 @@ mod tests {
         assert_eq!(result.take().unwrap(), "Done!");
     }
 
+    #[wasm_bindgen_test]
+    fn test_capture_latest() {
+        let body = Rc::new(body());
+
+        let (listener, latest) = capture_latest::<_, CustomEvent>(&body, "custom").unwrap();
+        assert!(latest.borrow().is_none());
+        for detail in ["first", "second"] {
+            let init = CustomEventInit::new();
+            init.set_detail(&JsValue::from(detail));
+            let event = CustomEvent::new_with_event_init_dict("custom", &init).unwrap();
+            body.dispatch_event(&event).unwrap();
+        }
+        assert_eq!(
+            latest.borrow().as_ref().unwrap().detail(),
+            JsValue::from("second")
+        );
+
+        drop(listener);
+        body.dispatch_event(&CustomEvent::new("custom").unwrap())
+            .unwrap();
+        assert_eq!(
+            latest.borrow().as_ref().unwrap().detail(),
+            JsValue::from("second")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_remove_listener() {
+        let body = Rc::new(body());
+
+        let count = Rc::new(Cell::new(0));
+        let count_clone = count.clone();
+        let mut listener = body
+            .when("ping", move |_: CustomEvent| {
+                count_clone.set(count_clone.get() + 1);
+            })
+            .unwrap();
+        assert!(listener.is_active());
+
+        body.dispatch_event(&CustomEvent::new("ping").unwrap())
+            .unwrap();
+        listener.remove();
+        assert!(!listener.is_active());
+        body.dispatch_event(&CustomEvent::new("ping").unwrap())
+            .unwrap();
+        listener.remove();
+
+        assert_eq!(count.get(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_listen_capturing() {
+        let body = Rc::new(body());
+        let input = document().create_element("input").unwrap();
+        let input = input.dyn_into::<HtmlElement>().unwrap();
+        body.append_child(&input).unwrap();
+
+        let mut stream: EventStream<_, FocusEvent> = body.listen_capturing("focus").unwrap();
+        input.focus().unwrap();
+        let event = stream.next().await.unwrap();
+        assert_eq!(
+            JsValue::from(event.target().unwrap()),
+            JsValue::from(input.clone())
+        );
+        input.remove();
+
+        let mut stream: EventStream<_, Event> = body.listen_capturing("custom").unwrap();
+        body.dispatch_event(&Event::new("custom").unwrap()).unwrap();
+        assert!(stream.next().await.is_some());
+        drop(stream);
+
+        // Listener left registered would invoke dropped closure,
+        // which is reported as an error.
+        let errors = Rc::new(Cell::new(0));
+        let errors_clone = errors.clone();
+        let _listener = Rc::new(window())
+            .when("error", move |_: Event| {
+                errors_clone.set(errors_clone.get() + 1)
+            })
+            .unwrap();
+        body.dispatch_event(&Event::new("custom").unwrap()).unwrap();
+        assert_eq!(errors.get(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_listen_timed() {
+        let body = Rc::new(body());
+
+        let mut stream = body.listen_timed::<MouseEvent>("click").unwrap();
+        body.click();
+        sleep(Duration::from_millis(100)).await;
+        body.click();
+        let (_, first) = stream.next().await.unwrap();
+        let (event, second) = stream.next().await.unwrap();
+        assert_eq!(event.time_stamp(), second);
+        assert!(second - first >= 100.0);
+    }
+
     #[wasm_bindgen_test]
     async fn test_event_stream() {
         let body = Rc::new(body());
@@ -246,4 +1293,134 @@ mod tests {
             vec![1, 2, 3]
         );
     }
+
+    #[wasm_bindgen_test]
+    async fn test_once() {
+        let body = Rc::new(body());
+
+        let mut next = body.once::<CustomEvent>("ping").unwrap();
+        assert!(!next.is_terminated());
+
+        for detail in [1, 2] {
+            let init = CustomEventInit::new();
+            init.set_detail(&JsValue::from(detail));
+            let event = CustomEvent::new_with_event_init_dict("ping", &init).unwrap();
+            body.dispatch_event(&event).unwrap();
+        }
+
+        let event = (&mut next).await;
+        assert_eq!(event.detail().as_f64(), Some(1.0));
+        assert!(next.is_terminated());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_when_or_timeout() {
+        let body = Rc::new(body());
+
+        let result = when_or_timeout::<_, CustomEvent>(&body, "ping", Duration::from_millis(100))
+            .unwrap()
+            .await;
+        assert!(result.is_err());
+
+        let next =
+            when_or_timeout::<_, CustomEvent>(&body, "ping", Duration::from_secs(10)).unwrap();
+        body.dispatch_event(&CustomEvent::new("ping").unwrap())
+            .unwrap();
+        assert!(next.await.is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_raf_throttled() {
+        let body = Rc::new(body());
+
+        let mut stream: EventStream<_, CustomEvent> = body.listen_raf_throttled("ping").unwrap();
+        for detail in [1, 2, 3] {
+            let init = CustomEventInit::new();
+            init.set_detail(&JsValue::from(detail));
+            let event = CustomEvent::new_with_event_init_dict("ping", &init).unwrap();
+            body.dispatch_event(&event).unwrap();
+        }
+
+        let event = stream.next().await.unwrap();
+        assert_eq!(event.detail().as_f64(), Some(3.0));
+        assert!(stream.next().now_or_never().is_none());
+
+        let init = CustomEventInit::new();
+        init.set_detail(&JsValue::from(4));
+        let event = CustomEvent::new_with_event_init_dict("ping", &init).unwrap();
+        body.dispatch_event(&event).unwrap();
+        stream.stop();
+        let event = stream.next().await.unwrap();
+        assert_eq!(event.detail().as_f64(), Some(4.0));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_drag_stream() {
+        let body = Rc::new(body());
+        let dispatch = |event_type: &str, pointer_id: i32, client_x: i32| {
+            let init = PointerEventInit::new();
+            init.set_pointer_id(pointer_id);
+            init.set_client_x(client_x);
+            let event = PointerEvent::new_with_event_init_dict(event_type, &init).unwrap();
+            body.dispatch_event(&event).unwrap();
+        };
+
+        let stream = drag_stream(&body).unwrap();
+        dispatch("pointermove", 1, 0);
+        dispatch("pointerdown", 1, 0);
+        dispatch("pointermove", 1, 1);
+        dispatch("pointermove", 2, 2);
+        dispatch("pointermove", 1, 3);
+        dispatch("pointerup", 1, 3);
+        dispatch("pointermove", 1, 4);
+
+        assert_eq!(
+            stream
+                .map(|event| event.client_x())
+                .collect::<Vec<_>>()
+                .await,
+            vec![1, 3]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_times() {
+        assert_eq!(
+            parse_times("0.3s, 150ms,1s"),
+            vec![
+                Duration::from_millis(300),
+                Duration::from_millis(150),
+                Duration::from_secs(1)
+            ]
+        );
+        assert_eq!(parse_times("0s"), vec![Duration::ZERO]);
+        assert_eq!(
+            parse_times("-1s, oops"),
+            vec![Duration::ZERO, Duration::ZERO]
+        );
+        assert!(parse_times("").is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_on_transition_end() {
+        let body = Rc::new(body());
+        let mut done = Box::pin(on_transition_end(&body, Some("opacity")).unwrap());
+
+        let init = TransitionEventInit::new();
+        init.set_property_name("color");
+        let event = TransitionEvent::new_with_event_init_dict("transitionend", &init).unwrap();
+        body.dispatch_event(&event).unwrap();
+        assert!(done.as_mut().now_or_never().is_none());
+
+        init.set_property_name("opacity");
+        let event = TransitionEvent::new_with_event_init_dict("transitionend", &init).unwrap();
+        body.dispatch_event(&event).unwrap();
+        // Shorter than the fallback - only matching event can resolve it in time.
+        let timeout = sleep(Duration::from_millis(50));
+        assert!(matches!(select(done, timeout).await, Either::Left(_)));
+
+        // No transition declared - resolves thanks to the fallback.
+        on_transition_end(&body, None).unwrap().await;
+    }
 }
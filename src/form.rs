@@ -0,0 +1,83 @@
+//! Form-related utilities.
+
+use std::{collections::HashMap, rc::Rc};
+
+use wasm_bindgen::JsCast;
+use web_sys::{Event, FormData, HtmlFormElement};
+
+use crate::{
+    event::{listen_filter_map, EventStream},
+    JsError,
+};
+
+/// Create stream of `form` submissions.
+///
+/// Default action (page reload) of each `submit` event is prevented
+/// and the form's data at the time of submission is yielded instead.
+pub fn on_submit(
+    form: &Rc<HtmlFormElement>,
+) -> Result<EventStream<HtmlFormElement, FormData, Event>, JsError> {
+    let form_clone = form.clone();
+    listen_filter_map(form, "submit", move |event: Event| {
+        event.prevent_default();
+        FormData::new_with_form(&form_clone).ok()
+    })
+}
+
+/// Converts `form_data` into a map of field names to values.
+///
+/// Non-string values (files) are skipped. If field has multiple
+/// values only the last one is kept.
+pub fn form_data_to_map(form_data: &FormData) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for entry in form_data.entries().into_iter().flatten() {
+        let entry: js_sys::Array = entry.unchecked_into();
+        if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string()) {
+            map.insert(name, value);
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, rc::Rc};
+
+    use futures::StreamExt;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use web_sys::{FormData, HtmlFormElement};
+
+    use crate::{
+        body, document,
+        form::{form_data_to_map, on_submit},
+    };
+
+    #[wasm_bindgen_test]
+    fn test_form_data_to_map() {
+        let form_data = FormData::new().unwrap();
+        form_data.append_with_str("name", "Alice").unwrap();
+        form_data.append_with_str("city", "Paris").unwrap();
+
+        let expected = HashMap::from([
+            ("name".to_string(), "Alice".to_string()),
+            ("city".to_string(), "Paris".to_string()),
+        ]);
+        assert_eq!(form_data_to_map(&form_data), expected);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_on_submit() {
+        let form: HtmlFormElement = document().create_element("form").unwrap().unchecked_into();
+        form.set_inner_html(r#"<input name="query" value="rust">"#);
+        body().append_child(&form).unwrap();
+        let form = Rc::new(form);
+
+        let mut submissions = on_submit(&form).unwrap();
+        form.request_submit().unwrap();
+        let form_data = submissions.next().await.unwrap();
+        assert_eq!(form_data.get("query").as_string().unwrap(), "rust");
+
+        form.remove();
+    }
+}
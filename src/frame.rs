@@ -0,0 +1,189 @@
+//! Animation frames.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt};
+
+use crate::{window, window::FrameRequest};
+
+/// Creates stream of animation frames.
+///
+/// Each item is the frame's timestamp (in milliseconds) passed
+/// to [`requestAnimationFrame`](https://developer.mozilla.org/en-US/docs/Web/API/Window/requestAnimationFrame)
+/// callback. Next frame is requested only when stream is polled.
+pub fn animation_frames() -> AnimationFrames {
+    let state = Rc::new(RefCell::new(State {
+        time: None,
+        waker: None,
+    }));
+    let state_clone = state.clone();
+    let request = FrameRequest::new(move |time| {
+        let mut state = state_clone.borrow_mut();
+        state.time = Some(time);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    });
+    AnimationFrames { state, request }
+}
+
+/// Stream returned by [`animation_frames`].
+#[derive(Debug)]
+pub struct AnimationFrames {
+    state: Rc<RefCell<State>>,
+    request: FrameRequest,
+}
+
+#[derive(Debug)]
+struct State {
+    time: Option<f64>,
+    waker: Option<Waker>,
+}
+
+impl Stream for AnimationFrames {
+    type Item = f64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.borrow_mut();
+        if let Some(time) = state.time.take() {
+            Poll::Ready(Some(time))
+        } else {
+            state.waker = Some(cx.waker().clone());
+            drop(state);
+            self.request.request();
+            Poll::Pending
+        }
+    }
+}
+
+/// Tracker of per-frame work duration against a time budget.
+///
+/// Call [`next_frame`] to wait for the next animation frame and
+/// [`done`] once that frame's work is finished.
+///
+/// [`next_frame`]: FrameBudget::next_frame
+/// [`done`]: FrameBudget::done
+#[derive(Debug)]
+pub struct FrameBudget {
+    budget: Duration,
+    frames: AnimationFrames,
+    started: Option<f64>,
+    samples: VecDeque<Duration>,
+    over_budget: usize,
+}
+
+impl FrameBudget {
+    /// Count of latest frames averaged by [`average_frame_time`].
+    ///
+    /// [`average_frame_time`]: FrameBudget::average_frame_time
+    pub const WINDOW: usize = 60;
+
+    /// Creates new tracker with given per-frame `budget`.
+    pub fn new(budget: Duration) -> Self {
+        FrameBudget {
+            budget,
+            frames: animation_frames(),
+            started: None,
+            samples: VecDeque::with_capacity(Self::WINDOW),
+            over_budget: 0,
+        }
+    }
+
+    /// Waits for the next animation frame and starts measuring its work.
+    ///
+    /// Returns the frame's timestamp - frame time is measured from it,
+    /// so delay before awaiting task resumes is included.
+    pub async fn next_frame(&mut self) -> f64 {
+        let time = self.frames.next().await.unwrap_or_default();
+        self.started = Some(time);
+        time
+    }
+
+    /// Signals that current frame's work is done.
+    ///
+    /// Returns time spent on the frame (zero if no frame was started).
+    pub fn done(&mut self) -> Duration {
+        let Some(started) = self.started.take() else {
+            return Duration::ZERO;
+        };
+        let now = window()
+            .performance()
+            .expect("window should have performance")
+            .now();
+        let frame_time = Duration::from_secs_f64((now - started).max(0.0) / 1000.0);
+        if frame_time > self.budget {
+            self.over_budget += 1;
+        }
+        if self.samples.len() == Self::WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(frame_time);
+        frame_time
+    }
+
+    /// Returns per-frame budget.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Returns count of frames that exceeded the budget.
+    pub fn over_budget_count(&self) -> usize {
+        self.over_budget
+    }
+
+    /// Returns average time spent on the latest [`WINDOW`] frames.
+    ///
+    /// [`WINDOW`]: FrameBudget::WINDOW
+    pub fn average_frame_time(&self) -> Duration {
+        if self.samples.is_empty() {
+            Duration::ZERO
+        } else {
+            self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{
+        frame::{animation_frames, FrameBudget},
+        sleep::Instant,
+    };
+
+    #[wasm_bindgen_test]
+    async fn test_animation_frames() {
+        let mut frames = animation_frames();
+        let first = frames.next().await.unwrap();
+        let second = frames.next().await.unwrap();
+        assert!(second > first);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_frame_budget() {
+        let mut budget = FrameBudget::new(Duration::from_millis(1));
+        assert_eq!(budget.done(), Duration::ZERO);
+
+        budget.next_frame().await;
+        let started = Instant::now();
+        while Instant::now() - started < Duration::from_millis(2) {}
+        assert!(budget.done() >= Duration::from_millis(2));
+
+        budget.next_frame().await;
+        budget.done();
+
+        assert!(budget.over_budget_count() >= 1);
+        assert!(budget.average_frame_time() >= Duration::from_millis(1));
+    }
+}
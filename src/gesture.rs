@@ -0,0 +1,360 @@
+//! Pointer gesture recognition.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use futures::{FutureExt, Stream};
+use web_sys::{EventTarget, PointerEvent};
+
+use crate::{
+    event::{EventListener, When},
+    sleep::{sleep, Instant, Sleep},
+    JsError,
+};
+
+/// How long pointer has to be held down (without moving) to recognize [`Gesture::LongPress`].
+pub const LONG_PRESS: Duration = Duration::from_millis(500);
+
+/// Maximum time between two taps recognized as [`Gesture::DoubleTap`].
+pub const DOUBLE_TAP: Duration = Duration::from_millis(300);
+
+/// Maximum duration of [`Gesture::Swipe`].
+pub const SWIPE_TIME: Duration = Duration::from_millis(500);
+
+/// Minimum distance (in CSS pixels) pointer has to travel to recognize [`Gesture::Swipe`].
+pub const SWIPE_DISTANCE: f64 = 30.0;
+
+/// Maximum distance (in CSS pixels) pointer can travel during tap or long press.
+pub const TAP_SLOP: f64 = 10.0;
+
+/// Recognized gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// Pointer pressed and released in place.
+    Tap,
+    /// Second tap following previous one within [`DOUBLE_TAP`].
+    ///
+    /// Previous tap is still reported as [`Gesture::Tap`].
+    DoubleTap,
+    /// Pointer held down in place for [`LONG_PRESS`].
+    LongPress,
+    /// Pointer quickly moved in given direction.
+    Swipe(Direction),
+}
+
+/// Direction of [`Gesture::Swipe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards the left edge of the screen.
+    Left,
+    /// Towards the right edge of the screen.
+    Right,
+    /// Towards the top edge of the screen.
+    Up,
+    /// Towards the bottom edge of the screen.
+    Down,
+}
+
+/// Create stream of gestures performed over `element`.
+///
+/// Only one pointer is tracked at a time. To receive swipes from touch input
+/// element should have `touch-action` CSS property set to `none`.
+pub fn gestures<T>(element: &Rc<T>) -> Result<Gestures<T>, JsError>
+where
+    T: When,
+{
+    let state = Rc::new(RefCell::new(GesturesState {
+        recognizer: Recognizer::default(),
+        queue: VecDeque::new(),
+        timer: None,
+        waker: None,
+    }));
+
+    let state_clone = state.clone();
+    let down = element.when("pointerdown", move |event: PointerEvent| {
+        let mut state = state_clone.borrow_mut();
+        if state
+            .recognizer
+            .down(event.pointer_id(), position(&event), Instant::now())
+        {
+            state.timer = Some(sleep(LONG_PRESS));
+            state.wake();
+        }
+    })?;
+    let state_clone = state.clone();
+    let moves = element.when("pointermove", move |event: PointerEvent| {
+        state_clone
+            .borrow_mut()
+            .recognizer
+            .moved(event.pointer_id(), position(&event));
+    })?;
+    let state_clone = state.clone();
+    let up = element.when("pointerup", move |event: PointerEvent| {
+        let mut state = state_clone.borrow_mut();
+        let gesture = state
+            .recognizer
+            .up(event.pointer_id(), position(&event), Instant::now());
+        if let Some(gesture) = gesture {
+            state.queue.push_back(gesture);
+            state.wake();
+        }
+    })?;
+    let state_clone = state.clone();
+    let cancel = element.when("pointercancel", move |event: PointerEvent| {
+        state_clone
+            .borrow_mut()
+            .recognizer
+            .cancel(event.pointer_id());
+    })?;
+
+    Ok(Gestures {
+        state,
+        _listeners: vec![down, moves, up, cancel],
+    })
+}
+
+fn position(event: &PointerEvent) -> (f64, f64) {
+    (event.client_x() as f64, event.client_y() as f64)
+}
+
+/// Stream of recognized gestures.
+///
+/// This `struct` is created by the [`gestures`] function.
+pub struct Gestures<T>
+where
+    T: AsRef<EventTarget>,
+{
+    state: Rc<RefCell<GesturesState>>,
+    _listeners: Vec<EventListener<T, PointerEvent>>,
+}
+
+struct GesturesState {
+    recognizer: Recognizer,
+    queue: VecDeque<Gesture>,
+    timer: Option<Sleep>,
+    waker: Option<Waker>,
+}
+
+impl GesturesState {
+    fn wake(&self) {
+        if let Some(waker) = &self.waker {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+impl<T> Stream for Gestures<T>
+where
+    T: AsRef<EventTarget>,
+{
+    type Item = Gesture;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        if let Some(gesture) = state.queue.pop_front() {
+            return Poll::Ready(Some(gesture));
+        }
+        if let Some(timer) = &mut state.timer {
+            if timer.poll_unpin(cx).is_ready() {
+                state.timer = None;
+                if let Some(gesture) = state.recognizer.long_press_elapsed() {
+                    return Poll::Ready(Some(gesture));
+                }
+            }
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Gesture recognition state machine, fed with pointer events.
+#[derive(Debug, Default)]
+struct Recognizer {
+    press: Option<Press>,
+    last_tap: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Press {
+    pointer: i32,
+    start: (f64, f64),
+    time: Instant,
+    moved: bool,
+    long_pressed: bool,
+}
+
+impl Recognizer {
+    /// Returns `true` if press started being tracked.
+    fn down(&mut self, pointer: i32, position: (f64, f64), now: Instant) -> bool {
+        if self.press.is_some() {
+            return false;
+        }
+        self.press = Some(Press {
+            pointer,
+            start: position,
+            time: now,
+            moved: false,
+            long_pressed: false,
+        });
+        true
+    }
+
+    fn moved(&mut self, pointer: i32, position: (f64, f64)) {
+        if let Some(press) = self.press.as_mut().filter(|press| press.pointer == pointer) {
+            if distance(press.start, position) > TAP_SLOP {
+                press.moved = true;
+            }
+        }
+    }
+
+    fn up(&mut self, pointer: i32, position: (f64, f64), now: Instant) -> Option<Gesture> {
+        if self.press.as_ref()?.pointer != pointer {
+            return None;
+        }
+        let press = self.press.take()?;
+        if press.long_pressed {
+            return None;
+        }
+
+        let (dx, dy) = (position.0 - press.start.0, position.1 - press.start.1);
+        if distance(press.start, position) >= SWIPE_DISTANCE {
+            if now - press.time > SWIPE_TIME {
+                return None;
+            }
+            let direction = if dx.abs() > dy.abs() {
+                if dx > 0.0 {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                }
+            } else if dy > 0.0 {
+                Direction::Down
+            } else {
+                Direction::Up
+            };
+            return Some(Gesture::Swipe(direction));
+        }
+        if press.moved || distance(press.start, position) > TAP_SLOP {
+            return None;
+        }
+
+        match self.last_tap.take() {
+            Some(last_tap) if now - last_tap <= DOUBLE_TAP => Some(Gesture::DoubleTap),
+            _ => {
+                self.last_tap = Some(now);
+                Some(Gesture::Tap)
+            }
+        }
+    }
+
+    fn cancel(&mut self, pointer: i32) {
+        if self
+            .press
+            .as_ref()
+            .is_some_and(|press| press.pointer == pointer)
+        {
+            self.press = None;
+        }
+    }
+
+    fn long_press_elapsed(&mut self) -> Option<Gesture> {
+        let press = self.press.as_mut()?;
+        if press.moved || press.long_pressed {
+            return None;
+        }
+        press.long_pressed = true;
+        self.last_tap = None;
+        Some(Gesture::LongPress)
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, time::Duration};
+
+    use futures::StreamExt;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use web_sys::{PointerEvent, PointerEventInit};
+
+    use crate::{
+        body,
+        gesture::{gestures, Direction, Gesture, Recognizer},
+        sleep::Instant,
+    };
+
+    #[wasm_bindgen_test]
+    fn test_recognizer() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut recognizer = Recognizer::default();
+
+        assert!(recognizer.down(1, (0.0, 0.0), at(0)));
+        assert!(!recognizer.down(2, (0.0, 0.0), at(10)));
+        assert_eq!(recognizer.up(2, (0.0, 0.0), at(20)), None);
+        assert_eq!(recognizer.up(1, (2.0, 0.0), at(50)), Some(Gesture::Tap));
+        recognizer.down(1, (0.0, 0.0), at(150));
+        assert_eq!(
+            recognizer.up(1, (0.0, 0.0), at(200)),
+            Some(Gesture::DoubleTap)
+        );
+
+        recognizer.down(1, (0.0, 0.0), at(1000));
+        assert_eq!(recognizer.long_press_elapsed(), Some(Gesture::LongPress));
+        assert_eq!(recognizer.up(1, (0.0, 0.0), at(1600)), None);
+
+        recognizer.down(1, (0.0, 0.0), at(2000));
+        recognizer.moved(1, (-20.0, 5.0));
+        assert_eq!(recognizer.long_press_elapsed(), None);
+        assert_eq!(
+            recognizer.up(1, (-50.0, 5.0), at(2200)),
+            Some(Gesture::Swipe(Direction::Left))
+        );
+
+        recognizer.down(1, (0.0, 0.0), at(3000));
+        assert_eq!(recognizer.up(1, (0.0, 80.0), at(4000)), None);
+
+        recognizer.down(1, (0.0, 0.0), at(5000));
+        recognizer.cancel(1);
+        assert_eq!(recognizer.up(1, (0.0, 0.0), at(5050)), None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_gestures() {
+        let body = Rc::new(body());
+        let dispatch = |event_type: &str, client_x: i32| {
+            let init = PointerEventInit::new();
+            init.set_pointer_id(1);
+            init.set_client_x(client_x);
+            let event = PointerEvent::new_with_event_init_dict(event_type, &init).unwrap();
+            body.dispatch_event(&event).unwrap();
+        };
+
+        let mut gestures = gestures(&body).unwrap();
+        dispatch("pointerdown", 0);
+        dispatch("pointerup", 0);
+        assert_eq!(gestures.next().await, Some(Gesture::Tap));
+
+        dispatch("pointerdown", 0);
+        dispatch("pointermove", 40);
+        dispatch("pointerup", 100);
+        assert_eq!(
+            gestures.next().await,
+            Some(Gesture::Swipe(Direction::Right))
+        );
+
+        dispatch("pointerdown", 0);
+        assert_eq!(gestures.next().await, Some(Gesture::LongPress));
+        dispatch("pointerup", 0);
+    }
+}
@@ -0,0 +1,179 @@
+//! Keyboard shortcuts.
+
+use std::{fmt::Debug, rc::Rc};
+
+use web_sys::KeyboardEvent;
+
+use crate::{
+    event::{EventListener, When},
+    JsError,
+};
+
+type Callback = Box<dyn FnMut(KeyboardEvent)>;
+
+/// Set of keyboard shortcuts dispatched by a single `keydown` listener.
+#[derive(Default)]
+pub struct Keymap {
+    bindings: Vec<(KeyCombo, Callback)>,
+}
+
+impl Keymap {
+    /// Creates new empty keymap.
+    pub fn new() -> Self {
+        Keymap::default()
+    }
+
+    /// Binds `callback` to key combination `combo`.
+    ///
+    /// `combo` consists of optional modifiers (`ctrl`, `shift`, `alt`, `meta`)
+    /// followed by a key, joined with `+` - for example `"ctrl+shift+z"`.
+    /// Key is matched case-insensitively against [`KeyboardEvent::key`],
+    /// aliases `space`, `plus` and `esc` are supported.
+    ///
+    /// This function panics if `combo` is invalid.
+    pub fn bind<F: FnMut(KeyboardEvent) + 'static>(mut self, combo: &str, callback: F) -> Self {
+        let combo = KeyCombo::parse(combo)
+            .unwrap_or_else(|| panic!("invalid key combination: {:?}", combo));
+        self.bindings.push((combo, Box::new(callback)));
+        self
+    }
+
+    /// Registers `keydown` listener on `target` dispatching to the matching binding.
+    ///
+    /// Default action of matched events is prevented.
+    pub fn attach<T: When>(
+        mut self,
+        target: &Rc<T>,
+    ) -> Result<EventListener<T, KeyboardEvent>, JsError> {
+        target.when("keydown", move |event: KeyboardEvent| {
+            if let Some((_, callback)) = self
+                .bindings
+                .iter_mut()
+                .find(|(combo, _)| combo.matches(&event))
+            {
+                event.prevent_default();
+                callback(event);
+            }
+        })
+    }
+}
+
+impl Debug for Keymap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.bindings.iter().map(|(combo, _)| combo))
+            .finish()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct KeyCombo {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+    key: String,
+}
+
+impl KeyCombo {
+    fn parse(combo: &str) -> Option<Self> {
+        let mut result = KeyCombo::default();
+        let mut parts = combo.split('+').map(|part| part.trim().to_lowercase());
+        let key = parts.next_back()?;
+        for modifier in parts {
+            let flag = match modifier.as_str() {
+                "ctrl" | "control" => &mut result.ctrl,
+                "shift" => &mut result.shift,
+                "alt" | "option" => &mut result.alt,
+                "meta" | "cmd" => &mut result.meta,
+                _ => return None,
+            };
+            if *flag {
+                return None;
+            }
+            *flag = true;
+        }
+        result.key = match key.as_str() {
+            "" => return None,
+            "space" => " ".to_string(),
+            "plus" => "+".to_string(),
+            "esc" => "escape".to_string(),
+            _ => key,
+        };
+        Some(result)
+    }
+
+    fn matches(&self, event: &KeyboardEvent) -> bool {
+        self.ctrl == event.ctrl_key()
+            && self.shift == event.shift_key()
+            && self.alt == event.alt_key()
+            && self.meta == event.meta_key()
+            && self.key == event.key().to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use web_sys::{KeyboardEvent, KeyboardEventInit};
+
+    use crate::{
+        body,
+        keymap::{KeyCombo, Keymap},
+    };
+
+    #[wasm_bindgen_test]
+    fn test_parse() {
+        assert_eq!(
+            KeyCombo::parse("Ctrl+Shift+Z"),
+            Some(KeyCombo {
+                ctrl: true,
+                shift: true,
+                key: "z".to_string(),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            KeyCombo::parse("esc"),
+            Some(KeyCombo {
+                key: "escape".to_string(),
+                ..Default::default()
+            })
+        );
+        assert_eq!(KeyCombo::parse(""), None);
+        assert_eq!(KeyCombo::parse("ctrl+"), None);
+        assert_eq!(KeyCombo::parse("ctrl+ctrl+s"), None);
+        assert_eq!(KeyCombo::parse("hyper+s"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_attach() {
+        let body = Rc::new(body());
+        let pressed = Rc::new(RefCell::new(Vec::new()));
+        let (save, redo) = (pressed.clone(), pressed.clone());
+        let _listener = Keymap::new()
+            .bind("ctrl+s", move |_| save.borrow_mut().push("save"))
+            .bind("ctrl+shift+z", move |_| redo.borrow_mut().push("redo"))
+            .attach(&body)
+            .unwrap();
+
+        let dispatch = |key: &str, ctrl: bool, shift: bool| {
+            let init = KeyboardEventInit::new();
+            init.set_key(key);
+            init.set_ctrl_key(ctrl);
+            init.set_shift_key(shift);
+            init.set_cancelable(true);
+            let event = KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+            body.dispatch_event(&event).unwrap();
+            event.default_prevented()
+        };
+
+        assert!(dispatch("s", true, false));
+        assert!(!dispatch("s", false, false));
+        assert!(dispatch("Z", true, true));
+        assert!(!dispatch("z", true, false));
+        assert_eq!(*pressed.borrow(), vec!["save", "redo"]);
+    }
+}
@@ -18,11 +18,35 @@ pub use queue::Queue;
 
 #[cfg(feature = "event")]
 pub mod event;
+#[cfg(feature = "event")]
+pub mod form;
+#[cfg(all(feature = "event", feature = "sleep"))]
+pub mod gesture;
+#[cfg(feature = "event")]
+pub mod keymap;
+
+#[cfg(feature = "oneshot")]
+pub mod oneshot;
+
+#[cfg(feature = "frame")]
+pub mod frame;
+
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
 
-use std::fmt::Display;
+#[cfg(feature = "observe")]
+pub mod observe;
+
+pub mod document;
+pub mod error;
+pub use error::{ErrorReport, JsError};
+
+pub mod location;
+pub mod scroll;
+pub mod window;
 
 use wasm_bindgen::prelude::*;
-use web_sys::{Document, HtmlElement, Window};
+use web_sys::{Document, HtmlElement, HtmlHeadElement, Window};
 
 /// Sets a panic hook that forwards panic messages to
 /// [`console.error`](https://developer.mozilla.org/en-US/docs/Web/API/Console/error).
@@ -110,27 +134,18 @@ pub fn body() -> HtmlElement {
     document().body().expect("document should have a body")
 }
 
-/// Wrapper for [`JsValue`] errors implementing [`std::error::Error`].
-#[derive(Debug)]
-pub struct JsError(pub JsValue);
-
-impl Display for JsError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.0)
-    }
-}
-
-impl std::error::Error for JsError {}
-
-impl From<JsValue> for JsError {
-    fn from(value: JsValue) -> Self {
-        JsError(value)
-    }
+/// Gets document's head.
+///
+/// This function panics when head doesn't exist in document or
+/// if document doesn't exist in window or
+/// if window doesn't exist.
+pub fn head() -> HtmlHeadElement {
+    document().head().expect("document should have a head")
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{body, document, window};
+    use crate::{body, document, head, window};
     use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
 
     wasm_bindgen_test_configure!(run_in_browser);
@@ -149,4 +164,9 @@ mod tests {
     fn test_document() {
         document();
     }
+
+    #[wasm_bindgen_test]
+    fn test_head() {
+        head();
+    }
 }
@@ -0,0 +1,41 @@
+//! Location-related utilities.
+
+use web_sys::Location;
+
+use crate::{window, JsError};
+
+/// Gets location object of the window.
+///
+/// This function panics if window doesn't exist.
+pub fn location() -> Location {
+    window().location()
+}
+
+/// Gets fragment identifier of the current URL, including leading `#`.
+///
+/// Returns empty string if URL has no fragment identifier.
+pub fn hash() -> Result<String, JsError> {
+    Ok(location().hash()?)
+}
+
+/// Sets fragment identifier of the current URL.
+///
+/// Leading `#` is optional.
+pub fn set_hash(hash: &str) -> Result<(), JsError> {
+    Ok(location().set_hash(hash)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::location::{hash, set_hash};
+
+    #[wasm_bindgen_test]
+    fn test_hash() {
+        set_hash("first").unwrap();
+        assert_eq!(hash().unwrap(), "#first");
+        set_hash("#second").unwrap();
+        assert_eq!(hash().unwrap(), "#second");
+    }
+}
@@ -0,0 +1,143 @@
+//! DOM observation utilities.
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    rc::Rc,
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+use futures::future::{poll_fn, select, Either};
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::{Element, MutationObserver, MutationObserverInit};
+
+use crate::{
+    document,
+    error::{Elapsed, JsError},
+    sleep::sleep,
+};
+
+/// Waits for element matching `selector` to appear in the document,
+/// giving up after `timeout`.
+///
+/// Resolves immediately if such element already exists, otherwise document
+/// is watched with a `MutationObserver` (disconnected once future completes
+/// or is dropped).
+pub fn wait_for_element(
+    selector: &str,
+    timeout: Duration,
+) -> Result<impl Future<Output = Result<Element, Elapsed>>, JsError> {
+    let existing = document().query_selector(selector)?;
+    let observer = match existing {
+        Some(_) => None,
+        None => Some(ElementObserver::new(selector)?),
+    };
+    let sleep = sleep(timeout);
+    Ok(async move {
+        let Some(observer) = observer else {
+            return Ok(existing.unwrap());
+        };
+        let appeared = poll_fn(|cx| {
+            let mut state = observer.state.borrow_mut();
+            match state.element.take() {
+                Some(element) => Poll::Ready(element),
+                None => {
+                    state.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        });
+        futures::pin_mut!(appeared);
+        match select(appeared, sleep).await {
+            Either::Left((element, _)) => Ok(element),
+            Either::Right(_) => Err(Elapsed(())),
+        }
+    })
+}
+
+struct ObserverState {
+    element: Option<Element>,
+    waker: Option<Waker>,
+}
+
+struct ElementObserver {
+    state: Rc<RefCell<ObserverState>>,
+    observer: MutationObserver,
+    _callback: Closure<dyn FnMut()>,
+}
+
+impl ElementObserver {
+    fn new(selector: &str) -> Result<Self, JsError> {
+        let state = Rc::new(RefCell::new(ObserverState {
+            element: None,
+            waker: None,
+        }));
+        let state_clone = state.clone();
+        let selector = selector.to_string();
+        let callback = Closure::<dyn FnMut()>::new(move || {
+            if let Ok(Some(element)) = document().query_selector(&selector) {
+                let waker = {
+                    let mut state = state_clone.borrow_mut();
+                    state.element = Some(element);
+                    state.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+        });
+        let observer = MutationObserver::new(callback.as_ref().unchecked_ref())?;
+        let options = MutationObserverInit::new();
+        options.set_child_list(true);
+        options.set_subtree(true);
+        options.set_attributes(true);
+        observer.observe_with_options(&document(), &options)?;
+        Ok(ElementObserver {
+            state,
+            observer,
+            _callback: callback,
+        })
+    }
+}
+
+impl Drop for ElementObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{body, document, observe::wait_for_element, sleep, spawn};
+
+    #[wasm_bindgen_test]
+    async fn test_wait_for_element() {
+        let element = document().create_element("div").unwrap();
+        element.set_id("existing-element");
+        body().append_child(&element).unwrap();
+        let found = wait_for_element("#existing-element", Duration::from_millis(100))
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(found, element);
+
+        let task = spawn(wait_for_element("#inserted-element", Duration::from_secs(1)).unwrap());
+        sleep(Duration::from_millis(100)).await;
+        let element = document().create_element("div").unwrap();
+        element.set_id("inserted-element");
+        body().append_child(&element).unwrap();
+        assert_eq!(task.await.unwrap().unwrap(), element);
+
+        assert!(
+            wait_for_element("#missing-element", Duration::from_millis(100))
+                .unwrap()
+                .await
+                .is_err()
+        );
+        assert!(wait_for_element("#", Duration::from_millis(100)).is_err());
+    }
+}
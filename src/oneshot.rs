@@ -0,0 +1,216 @@
+//! Single value channel.
+
+use futures::{future::FusedFuture, Future};
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Creates new oneshot channel, returning the sender/receiver halves.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let state = Rc::new(RefCell::new(State {
+        value: None,
+        waker: None,
+        sender_dropped: false,
+        receiver_dropped: false,
+    }));
+    (
+        Sender {
+            state: state.clone(),
+        },
+        Receiver {
+            state,
+            terminated: false,
+        },
+    )
+}
+
+/// Error returned by [`Receiver`] when [`Sender`] was dropped without sending a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Canceled;
+
+impl Display for Canceled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "oneshot canceled")
+    }
+}
+
+impl std::error::Error for Canceled {}
+
+struct State<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+}
+
+/// Sending half of oneshot channel.
+pub struct Sender<T> {
+    state: Rc<RefCell<State<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends `value` to the receiver.
+    ///
+    /// Returns `value` back if receiver was already dropped.
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut state = self.state.borrow_mut();
+        if state.receiver_dropped {
+            Err(value)
+        } else {
+            state.value = Some(value);
+            Ok(())
+        }
+    }
+
+    /// Returns `true` if receiver was dropped.
+    pub fn is_canceled(&self) -> bool {
+        self.state.borrow().receiver_dropped
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            state.sender_dropped = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Receiving half of oneshot channel.
+///
+/// Await it to receive the value.
+pub struct Receiver<T> {
+    state: Rc<RefCell<State<T>>>,
+    terminated: bool,
+}
+
+impl<T> Receiver<T> {
+    /// Receives value if it was already sent.
+    ///
+    /// Returns `Ok(None)` if value wasn't sent yet.
+    pub fn try_recv(&mut self) -> Result<Option<T>, Canceled> {
+        let mut state = self.state.borrow_mut();
+        if let Some(value) = state.value.take() {
+            self.terminated = true;
+            Ok(Some(value))
+        } else if state.sender_dropped {
+            self.terminated = true;
+            Err(Canceled)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.state.borrow_mut().receiver_dropped = true;
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.terminated {
+            return Poll::Pending;
+        }
+        match self.try_recv() {
+            Ok(Some(value)) => Poll::Ready(Ok(value)),
+            Err(error) => Poll::Ready(Err(error)),
+            Ok(None) => {
+                let mut state = self.state.borrow_mut();
+                let new_waker = cx.waker();
+                if let Some(waker) = &state.waker {
+                    if !waker.will_wake(new_waker) {
+                        state.waker = Some(new_waker.clone());
+                    }
+                } else {
+                    state.waker = Some(new_waker.clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> FusedFuture for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::future::FusedFuture;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{
+        oneshot::{channel, Canceled},
+        sleep, spawn,
+    };
+
+    #[wasm_bindgen_test]
+    async fn test_send() {
+        let (sender, mut receiver) = channel();
+        assert_eq!(receiver.try_recv(), Ok(None));
+
+        spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            sender.send(1).unwrap();
+        });
+
+        assert_eq!((&mut receiver).await, Ok(1));
+        assert!(receiver.is_terminated());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_canceled() {
+        let (sender, receiver) = channel::<i32>();
+        spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            drop(sender);
+        });
+        assert_eq!(receiver.await, Err(Canceled));
+
+        let (sender, receiver) = channel();
+        assert!(!sender.is_canceled());
+        drop(receiver);
+        assert!(sender.is_canceled());
+        assert_eq!(sender.send(1), Err(1));
+    }
+
+    /// Plain tests run on host target - see `test.sh`.
+    #[cfg(all(feature = "dev", not(target_arch = "wasm32")))]
+    mod host {
+        use std::task::Poll;
+
+        use crate::{
+            oneshot::{channel, Canceled},
+            spawn::poll_once,
+        };
+
+        #[test]
+        fn test_recv() {
+            let (sender, mut receiver) = channel();
+            assert_eq!(poll_once(&mut receiver), Poll::Pending);
+            sender.send(1).unwrap();
+            assert_eq!(poll_once(&mut receiver), Poll::Ready(Ok(1)));
+
+            let (sender, mut receiver) = channel::<i32>();
+            drop(sender);
+            assert_eq!(poll_once(&mut receiver), Poll::Ready(Err(Canceled)));
+        }
+    }
+}
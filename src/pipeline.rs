@@ -0,0 +1,230 @@
+//! Multi-stage processing pipelines.
+
+use futures::{Future, Stream, StreamExt};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::{
+    queue::Queue,
+    spawn::{JoinError, JoinHandle, Runtime},
+};
+
+/// Pipeline builder.
+///
+/// Each stage runs in its own spawned task, stages are connected
+/// by bounded queues - slow stage makes preceding stages wait.
+///
+/// Dropping pipeline (or [`PipelineHandle`] returned by [`sink`]) aborts
+/// all its stages.
+///
+/// [`sink`]: Pipeline::sink
+pub struct Pipeline<T> {
+    output: Rc<Queue<T>>,
+    stages: Stages,
+    capacity: usize,
+}
+
+/// Runtime of pipeline stages, aborting them when dropped.
+struct Stages(Runtime);
+
+impl Drop for Stages {
+    fn drop(&mut self) {
+        self.0.abort_all();
+    }
+}
+
+impl<T: 'static> Pipeline<T> {
+    /// Creates new pipeline fed by `source`.
+    ///
+    /// `capacity` is capacity of each queue connecting stages,
+    /// it must be greater than 0 - it'll panic otherwise.
+    pub fn new<S>(source: S, capacity: usize) -> Self
+    where
+        S: Stream<Item = T> + 'static,
+    {
+        let stages = Stages(Runtime::new());
+        let output = Rc::new(Queue::with_capacity(capacity));
+        let queue = output.clone();
+        stages.0.spawn(async move {
+            let mut source = Box::pin(source);
+            while let Some(element) = source.next().await {
+                if queue.push_backpressured(element).await.is_err() {
+                    break;
+                }
+            }
+            queue.close();
+        });
+
+        Pipeline {
+            output,
+            stages,
+            capacity,
+        }
+    }
+
+    /// Appends stage transforming elements with asynchronous function `f`.
+    pub fn stage<U, F, Fut>(self, mut f: F) -> Pipeline<U>
+    where
+        U: 'static,
+        F: FnMut(T) -> Fut + 'static,
+        Fut: Future<Output = U> + 'static,
+    {
+        let input = self.output;
+        let output = Rc::new(Queue::with_capacity(self.capacity));
+        let queue = output.clone();
+        self.stages.0.spawn(async move {
+            let mut elements = input.stream();
+            while let Some(element) = elements.next().await {
+                if queue.push_backpressured(f(element).await).await.is_err() {
+                    break;
+                }
+            }
+            input.close();
+            queue.close();
+        });
+
+        Pipeline {
+            output,
+            stages: self.stages,
+            capacity: self.capacity,
+        }
+    }
+
+    /// Terminates pipeline with asynchronous function `f` consuming its output.
+    ///
+    /// Returned handle can be awaited for pipeline completion.
+    pub fn sink<F, Fut>(self, mut f: F) -> PipelineHandle
+    where
+        F: FnMut(T) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let input = self.output;
+        let sink = self.stages.0.spawn(async move {
+            let mut elements = input.stream();
+            while let Some(element) = elements.next().await {
+                f(element).await;
+            }
+        });
+
+        PipelineHandle {
+            stages: self.stages,
+            sink,
+        }
+    }
+}
+
+/// Handle to a running [`Pipeline`].
+///
+/// Resolves when all elements went through the pipeline.
+/// Dropping it aborts all pipeline tasks.
+pub struct PipelineHandle {
+    stages: Stages,
+    sink: JoinHandle<()>,
+}
+
+impl PipelineHandle {
+    /// Aborts all pipeline tasks.
+    pub fn shutdown(&self) {
+        self.stages.0.abort_all();
+    }
+
+    /// Checks if pipeline finished (or was shut down).
+    pub fn is_finished(&self) -> bool {
+        self.sink.is_finished()
+    }
+}
+
+impl Future for PipelineHandle {
+    type Output = Result<(), JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.sink).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, StreamExt};
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::{pipeline::Pipeline, sleep::sleep};
+
+    #[wasm_bindgen_test]
+    async fn test_pipeline() {
+        let output = Rc::new(RefCell::new(vec![]));
+        let output_clone = output.clone();
+        Pipeline::new(stream::iter(1..=5), 1)
+            .stage(|x| async move { x * 2 })
+            .stage(|x| async move {
+                sleep(Duration::from_millis(10)).await;
+                x + 1
+            })
+            .sink(move |x| {
+                output_clone.borrow_mut().push(x);
+                async {}
+            })
+            .await
+            .unwrap();
+        assert_eq!(*output.borrow(), vec![3, 5, 7, 9, 11]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_shutdown() {
+        let output = Rc::new(RefCell::new(vec![]));
+        let output_clone = output.clone();
+        let handle = Pipeline::new(stream::iter(0..), 2)
+            .stage(|x| async move {
+                sleep(Duration::from_millis(50)).await;
+                x
+            })
+            .sink(move |x| {
+                output_clone.borrow_mut().push(x);
+                async {}
+            });
+        sleep(Duration::from_millis(120)).await;
+        handle.shutdown();
+        assert!(handle.is_finished());
+        let count = output.borrow().len();
+        sleep(Duration::from_millis(120)).await;
+        assert_eq!(output.borrow().len(), count);
+        assert!(handle.await.unwrap_err().is_cancelled());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_drop() {
+        let source = Rc::new(());
+        let source_clone = source.clone();
+        let pipeline = Pipeline::new(
+            stream::iter(0..).map(move |x| {
+                let _ = &source_clone;
+                x
+            }),
+            1,
+        );
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(Rc::strong_count(&source), 2);
+        drop(pipeline);
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(Rc::strong_count(&source), 1);
+
+        let source_clone = source.clone();
+        let handle = Pipeline::new(
+            stream::iter(0..).map(move |x| {
+                let _ = &source_clone;
+                x
+            }),
+            1,
+        )
+        .stage(|x| async move {
+            sleep(Duration::from_secs(10)).await;
+            x
+        })
+        .sink(|_| async {});
+        sleep(Duration::from_millis(50)).await;
+        drop(handle);
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(Rc::strong_count(&source), 1);
+    }
+}
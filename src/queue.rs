@@ -1,6 +1,10 @@
 //! Async queue.
 
-use futures::{future::FusedFuture, Future};
+use futures::{
+    future::{self, FusedFuture},
+    stream::FusedStream,
+    Future, Stream, StreamExt,
+};
 use std::{
     cell::RefCell,
     collections::VecDeque,
@@ -9,6 +13,9 @@ use std::{
     task::{Context, Poll, Waker},
 };
 
+#[cfg(feature = "spawn")]
+use crate::spawn::{spawn, JoinHandle};
+
 /// FIFO queue with async pop.
 pub struct Queue<T> {
     state: RefCell<State<T>>,
@@ -18,6 +25,9 @@ pub struct Queue<T> {
 struct State<T> {
     buffer: VecDeque<T>,
     wakers: VecDeque<Weak<RefCell<PopWaker>>>,
+    push_wakers: Vec<Waker>,
+    empty_wakers: Vec<Waker>,
+    closed: bool,
 }
 
 impl<T> State<T> {
@@ -25,12 +35,18 @@ impl<T> State<T> {
         State {
             buffer: VecDeque::new(),
             wakers: VecDeque::new(),
+            push_wakers: Vec::new(),
+            empty_wakers: Vec::new(),
+            closed: false,
         }
     }
 }
 
 impl<T> Queue<T> {
     /// Creates new queue with unbounded capacity.
+    ///
+    /// Internally such queue has capacity 0, which means "no limit" -
+    /// it is not a rendezvous channel (see [`Rendezvous`] for that).
     pub fn new() -> Self {
         Queue {
             state: RefCell::new(State::new()),
@@ -41,6 +57,9 @@ impl<T> Queue<T> {
     /// Creates new queue with given `capacity`.
     ///
     /// `capacity` must be greater than 0 - it'll panic otherwise.
+    /// For channel without any buffering use [`rendezvous`].
+    ///
+    /// [`rendezvous`]: Queue::rendezvous
     pub fn with_capacity(capacity: usize) -> Self {
         assert!(capacity > 0, "capacity must be greater than 0");
         Queue {
@@ -49,12 +68,28 @@ impl<T> Queue<T> {
         }
     }
 
+    /// Creates new [`Rendezvous`] channel - one that doesn't buffer elements
+    /// at all, handing them off directly from producers to consumers.
+    ///
+    /// Shorthand for [`Rendezvous::new`].
+    pub fn rendezvous() -> Rendezvous<T> {
+        Rendezvous::new()
+    }
+
     /// Pushes `element` into the queue.
     ///
     /// If queue is full it will push out the last (oldest) element
     /// out of the queue.
+    ///
+    /// If queue is closed `element` is dropped - use [`try_push`] to get
+    /// it back instead.
+    ///
+    /// [`try_push`]: Queue::try_push
     pub fn push(&self, element: T) {
         let mut state = self.state.borrow_mut();
+        if state.closed {
+            return;
+        }
         state.buffer.push_front(element);
         if self.capacity > 0 {
             state.buffer.truncate(self.capacity)
@@ -63,12 +98,104 @@ impl<T> Queue<T> {
         self.wake_next();
     }
 
+    /// Pushes `element` into the queue only if queue is neither full nor closed.
+    ///
+    /// Returns `element` back otherwise.
+    pub fn try_push(&self, element: T) -> Result<(), T> {
+        if self.is_full() || self.is_closed() {
+            Err(element)
+        } else {
+            self.push(element);
+            Ok(())
+        }
+    }
+
+    /// Pushes (asynchronously) `element` into the queue.
+    ///
+    /// Unlike [`push`] it never pushes elements out of the queue - if queue
+    /// is full `await` will wait till there's room for `element`.
+    ///
+    /// Returns `element` back if queue is (or gets) closed.
+    ///
+    /// [`push`]: Queue::push
+    #[must_use]
+    pub fn push_backpressured(&self, element: T) -> PushBackpressured<'_, T> {
+        PushBackpressured {
+            queue: self,
+            element: Some(element),
+        }
+    }
+
+    /// Pushes `element` into the queue right away.
+    ///
+    /// Must only be called after [`poll_push_ready`] returned `Ready` - it'll
+    /// panic if queue is full. Returns `element` back if queue is closed.
+    ///
+    /// [`poll_push_ready`]: Queue::poll_push_ready
+    pub fn push_now(&self, element: T) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(element);
+        }
+        assert!(
+            !self.is_full(),
+            "push_now called without poll_push_ready returning Ready"
+        );
+        self.push(element);
+        Ok(())
+    }
+
+    /// Closes the queue.
+    ///
+    /// Elements pushed after closing are rejected, elements already in the
+    /// queue can still be popped. Streams created with [`stream`] terminate
+    /// once closed queue is empty.
+    ///
+    /// [`stream`]: Queue::stream
+    pub fn close(&self) {
+        let wakers: Vec<_> = {
+            let mut state = self.state.borrow_mut();
+            state.closed = true;
+            state.wakers.drain(..).collect()
+        };
+        self.wake_pushers();
+        for waker in wakers {
+            if let Some(waker) = waker.upgrade() {
+                let waker = {
+                    let mut waker = waker.borrow_mut();
+                    waker.woken = true;
+                    waker.waker.clone()
+                };
+                waker.wake();
+            }
+        }
+    }
+
+    /// Creates weak handle to the queue.
+    ///
+    /// Useful for producers (like event callbacks) that shouldn't keep
+    /// the queue alive - this way reference cycles can be avoided.
+    pub fn downgrade(self: &Rc<Self>) -> WeakQueue<T> {
+        WeakQueue {
+            queue: Rc::downgrade(self),
+        }
+    }
+
+    /// Returns `true` if queue was closed.
+    pub fn is_closed(&self) -> bool {
+        self.state.borrow().closed
+    }
+
     /// Pops (asynchronously) element off the queue.
     ///
     /// It means that if queue is currently empty `await` will
     /// wait till element is pushed into the queue.
+    ///
+    /// Note that popping from an empty closed queue never completes,
+    /// use [`stream`] to detect closing.
+    ///
+    /// [`stream`]: Queue::stream
     #[must_use]
-    pub fn pop(&self) -> Pop<T> {
+    pub fn pop(&self) -> Pop<'_, T> {
         Pop {
             queue: self,
             terminated: false,
@@ -76,11 +203,73 @@ impl<T> Queue<T> {
         }
     }
 
+    /// Creates stream of elements popped off the queue.
+    ///
+    /// Queue remains usable directly while stream exists.
+    pub fn stream(&self) -> QueueStream<'_, T> {
+        QueueStream {
+            queue: self,
+            pop: None,
+        }
+    }
+
+    /// Creates stream of elements popped off the queue transformed with `f`.
+    ///
+    /// Shorthand for `queue.stream().map(f)`.
+    pub fn stream_map<'a, U, F>(&'a self, f: F) -> impl Stream<Item = U> + 'a
+    where
+        F: FnMut(T) -> U + 'a,
+    {
+        self.stream().map(f)
+    }
+
+    /// Pops elements until queue is closed (and empty), folding them
+    /// into accumulator with `f`.
+    ///
+    /// Resolves with final value of accumulator.
+    pub fn fold<'a, A, F>(&'a self, init: A, mut f: F) -> impl Future<Output = A> + 'a
+    where
+        A: 'a,
+        F: FnMut(A, T) -> A + 'a,
+    {
+        self.stream().fold(init, move |accumulator, element| {
+            future::ready(f(accumulator, element))
+        })
+    }
+
     /// Pops element off the queue.
     ///
     /// Returns `None` if queue is currently empty.
     pub fn try_pop(&self) -> Option<T> {
-        self.state.borrow_mut().buffer.pop_back()
+        let element = self.state.borrow_mut().buffer.pop_back();
+        if element.is_some() {
+            self.wake_popped();
+        }
+        element
+    }
+
+    /// Pops all elements currently in the queue.
+    pub fn drain(&self) -> Vec<T> {
+        let elements: Vec<_> = self.state.borrow_mut().buffer.drain(..).rev().collect();
+        if !elements.is_empty() {
+            self.wake_popped();
+        }
+        elements
+    }
+
+    /// Consumes the queue, returning all elements left in it (in pop order).
+    ///
+    /// To consume queue shared with [`Rc`] use [`Rc::try_unwrap`] first.
+    pub fn into_vec(self) -> Vec<T> {
+        self.state.into_inner().buffer.into_iter().rev().collect()
+    }
+
+    /// Waits until queue is empty.
+    ///
+    /// Resolves immediately if queue is already empty.
+    #[must_use]
+    pub fn wait_until_empty(&self) -> WaitUntilEmpty<'_, T> {
+        WaitUntilEmpty { queue: self }
     }
 
     /// Returns count of elements currently in the queue.
@@ -102,12 +291,64 @@ impl<T> Queue<T> {
         }
     }
 
+    fn wake_pushers(&self) {
+        let wakers = std::mem::take(&mut self.state.borrow_mut().push_wakers);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    fn wake_popped(&self) {
+        self.wake_pushers();
+        let wakers = {
+            let mut state = self.state.borrow_mut();
+            if state.buffer.is_empty() {
+                std::mem::take(&mut state.empty_wakers)
+            } else {
+                vec![]
+            }
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Checks if there's room for an element, registering current task
+    /// to be woken when there is otherwise.
+    ///
+    /// Returns `Ready` for closed queue too ([`push_now`] fails then).
+    /// Low-level primitive for custom flow-controlled producers, see also
+    /// [`push_backpressured`].
+    ///
+    /// [`push_now`]: Queue::push_now
+    /// [`push_backpressured`]: Queue::push_backpressured
+    pub fn poll_push_ready(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_closed() || !self.is_full() {
+            Poll::Ready(())
+        } else {
+            let mut state = self.state.borrow_mut();
+            if !state
+                .push_wakers
+                .iter()
+                .any(|waker| waker.will_wake(cx.waker()))
+            {
+                state.push_wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+
     fn wake_next(&self) {
-        while let Some(waker) = self.state.borrow_mut().wakers.pop_front() {
+        // No borrows may be held while waking - waker can synchronously
+        // poll or drop `Pop` futures of this queue
+        loop {
+            let next = self.state.borrow_mut().wakers.pop_front();
+            let Some(waker) = next else {
+                break;
+            };
             if let Some(waker) = waker.upgrade() {
-                let mut waker = waker.borrow_mut();
-                waker.woken = true;
-                waker.waker.wake_by_ref();
+                let waker = {
+                    let mut waker = waker.borrow_mut();
+                    waker.woken = true;
+                    waker.waker.clone()
+                };
+                waker.wake();
                 break;
             }
         }
@@ -120,6 +361,145 @@ impl<T> Default for Queue<T> {
     }
 }
 
+/// Spawns a worker pool processing elements popped off `queue` with `handler`.
+///
+/// Up to `concurrency` handlers run concurrently, new elements are popped
+/// as they finish. Task completes once `queue` is closed and all elements
+/// were processed.
+///
+/// `concurrency` must be greater than 0 - it'll panic otherwise.
+#[cfg(feature = "spawn")]
+pub fn spawn_workers<T, F, Fut>(
+    queue: Rc<Queue<T>>,
+    concurrency: usize,
+    handler: F,
+) -> JoinHandle<()>
+where
+    T: 'static,
+    F: FnMut(T) -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    assert!(concurrency > 0, "concurrency must be greater than 0");
+    spawn(async move {
+        queue
+            .stream()
+            .for_each_concurrent(concurrency, handler)
+            .await
+    })
+}
+
+/// Merges `sources` into a single stream ordered by `key`.
+///
+/// Each time the stream yields the source's head element with the smallest key
+/// (ties are resolved in favor of earlier sources). To compare heads it must
+/// hold one element from each source, so it waits whenever any open source
+/// is empty. Stream terminates once all sources are closed and empty.
+///
+/// If each source is ordered by `key` the resulting stream is ordered too.
+pub fn merge_by<T, K, F>(sources: Vec<Rc<Queue<T>>>, key: F) -> MergeBy<T, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    let count = sources.len();
+    MergeBy {
+        heads: (0..count).map(|_| None).collect(),
+        wakers: (0..count).map(|_| None).collect(),
+        sources,
+        key,
+    }
+}
+
+/// Stream returned by [`merge_by`] function.
+pub struct MergeBy<T, F> {
+    sources: Vec<Rc<Queue<T>>>,
+    heads: Vec<Option<T>>,
+    wakers: Vec<Option<Rc<RefCell<PopWaker>>>>,
+    key: F,
+}
+
+impl<T, K, F> Stream for MergeBy<T, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut waiting = false;
+        for ((source, head), waker) in this
+            .sources
+            .iter()
+            .zip(this.heads.iter_mut())
+            .zip(this.wakers.iter_mut())
+        {
+            if head.is_some() {
+                continue;
+            }
+            match source.poll_pop(waker, cx) {
+                Poll::Ready(element) => *head = Some(element),
+                Poll::Pending if source.is_closed() => source.release_waker(waker),
+                Poll::Pending => waiting = true,
+            }
+        }
+        if waiting {
+            return Poll::Pending;
+        }
+
+        let key = &this.key;
+        let next = this
+            .heads
+            .iter()
+            .enumerate()
+            .filter_map(|(index, head)| head.as_ref().map(|head| (index, key(head))))
+            .min_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(index, _)| index);
+        Poll::Ready(next.and_then(|index| this.heads[index].take()))
+    }
+}
+
+impl<T, F> Unpin for MergeBy<T, F> {}
+
+impl<T, F> Drop for MergeBy<T, F> {
+    fn drop(&mut self) {
+        for (source, waker) in self.sources.iter().zip(self.wakers.iter_mut()) {
+            source.release_waker(waker);
+        }
+    }
+}
+
+/// Weak handle to a [`Queue`] created with [downgrade] method.
+///
+/// [downgrade]: Queue::downgrade
+pub struct WeakQueue<T> {
+    queue: Weak<Queue<T>>,
+}
+
+impl<T> WeakQueue<T> {
+    /// Pushes `element` into the queue if it still exists.
+    ///
+    /// See [`Queue::push`].
+    pub fn push(&self, element: T) {
+        if let Some(queue) = self.upgrade() {
+            queue.push(element);
+        }
+    }
+
+    /// Returns strong handle to the queue if it still exists.
+    pub fn upgrade(&self) -> Option<Rc<Queue<T>>> {
+        self.queue.upgrade()
+    }
+}
+
+impl<T> Clone for WeakQueue<T> {
+    fn clone(&self) -> Self {
+        WeakQueue {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
 /// Future returned by [pop] method.
 ///
 /// [pop]: Queue::pop
@@ -149,19 +529,48 @@ impl PopWaker {
     }
 }
 
-impl<'a, T> Drop for Pop<'a, T> {
-    fn drop(&mut self) {
+impl<T> Queue<T> {
+    /// Pops element off the queue or registers `waker` to be woken
+    /// when element is pushed.
+    fn poll_pop(&self, waker: &mut Option<Rc<RefCell<PopWaker>>>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.borrow_mut();
+        match state.buffer.pop_back() {
+            Some(value) => {
+                drop(state);
+                *waker = None;
+                self.wake_popped();
+                Poll::Ready(value)
+            }
+            None => {
+                let waker = waker.get_or_insert_with(|| {
+                    Rc::new(RefCell::new(PopWaker::new(cx.waker().clone())))
+                });
+                {
+                    let mut waker = waker.borrow_mut();
+                    waker.update(cx.waker());
+                    waker.woken = false;
+                }
+                state.wakers.push_front(Rc::downgrade(waker));
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Releases `waker` registered by `poll_pop`.
+    fn release_waker(&self, waker: &mut Option<Rc<RefCell<PopWaker>>>) {
         // We were woken but didn't receive anything, wake up another
-        if self
-            .waker
-            .take()
-            .map_or(false, |waker| waker.borrow().woken)
-        {
-            self.queue.wake_next();
+        if waker.take().is_some_and(|waker| waker.borrow().woken) {
+            self.wake_next();
         }
     }
 }
 
+impl<'a, T> Drop for Pop<'a, T> {
+    fn drop(&mut self) {
+        self.queue.release_waker(&mut self.waker);
+    }
+}
+
 impl<'a, T> Future for Pop<'a, T> {
     type Output = T;
 
@@ -169,33 +578,516 @@ impl<'a, T> Future for Pop<'a, T> {
         if self.terminated {
             Poll::Pending
         } else {
-            let mut state = self.queue.state.borrow_mut();
-            match state.buffer.pop_back() {
-                Some(value) => {
-                    self.terminated = true;
-                    self.waker = None;
-                    Poll::Ready(value)
-                }
-                None => {
-                    if let Some(waker) = &self.waker {
-                        let mut waker = waker.borrow_mut();
-                        waker.update(cx.waker());
-                        waker.woken = false;
-                    } else {
-                        let waker = Rc::new(RefCell::new(PopWaker::new(cx.waker().clone())));
-                        self.waker = Some(waker);
-                    }
-                    state
-                        .wakers
-                        .push_front(Rc::downgrade(self.waker.as_ref().unwrap()));
-                    Poll::Pending
+            let this = &mut *self;
+            let result = this.queue.poll_pop(&mut this.waker, cx);
+            this.terminated = result.is_ready();
+            result
+        }
+    }
+}
+
+impl<'a, T> FusedFuture for Pop<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+/// Future returned by [push_backpressured] method.
+///
+/// [push_backpressured]: Queue::push_backpressured
+pub struct PushBackpressured<'a, T> {
+    queue: &'a Queue<T>,
+    element: Option<T>,
+}
+
+impl<'a, T> Unpin for PushBackpressured<'a, T> {}
+
+impl<'a, T> Future for PushBackpressured<'a, T> {
+    type Output = Result<(), T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.element.is_none() || self.queue.poll_push_ready(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let element = self.element.take().unwrap();
+        Poll::Ready(self.queue.try_push(element))
+    }
+}
+
+impl<'a, T> FusedFuture for PushBackpressured<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.element.is_none()
+    }
+}
+
+/// Future returned by [wait_until_empty] method.
+///
+/// [wait_until_empty]: Queue::wait_until_empty
+pub struct WaitUntilEmpty<'a, T> {
+    queue: &'a Queue<T>,
+}
+
+impl<'a, T> Future for WaitUntilEmpty<'a, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.queue.state.borrow_mut();
+        if state.buffer.is_empty() {
+            Poll::Ready(())
+        } else {
+            if !state
+                .empty_wakers
+                .iter()
+                .any(|waker| waker.will_wake(cx.waker()))
+            {
+                state.empty_wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+/// Stream returned by [stream] method.
+///
+/// [stream]: Queue::stream
+pub struct QueueStream<'a, T> {
+    queue: &'a Queue<T>,
+    pop: Option<Pop<'a, T>>,
+}
+
+impl<'a, T> Stream for QueueStream<'a, T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let queue = self.queue;
+        let pop = self.pop.get_or_insert_with(|| queue.pop());
+        match Pin::new(pop).poll(cx) {
+            Poll::Ready(element) => {
+                self.pop = None;
+                Poll::Ready(Some(element))
+            }
+            Poll::Pending if queue.is_closed() => {
+                self.pop = None;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'a, T> FusedStream for QueueStream<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.queue.is_closed() && self.queue.is_empty()
+    }
+}
+
+/// Queue retaining a bounded history of pushed elements.
+///
+/// Every subscriber first receives the retained history and then
+/// all elements pushed after it subscribed.
+pub struct ReplayQueue<T> {
+    state: RefCell<ReplayState<T>>,
+    capacity: usize,
+}
+
+struct ReplayState<T> {
+    history: VecDeque<T>,
+    subscribers: Vec<Weak<RefCell<Subscriber<T>>>>,
+}
+
+struct Subscriber<T> {
+    buffer: VecDeque<T>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+impl<T> ReplayQueue<T>
+where
+    T: Clone,
+{
+    /// Creates new replay queue retaining up to `capacity` latest elements.
+    ///
+    /// `capacity` must be greater than 0 - it'll panic otherwise.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        ReplayQueue {
+            state: RefCell::new(ReplayState {
+                history: VecDeque::new(),
+                subscribers: Vec::new(),
+            }),
+            capacity,
+        }
+    }
+
+    /// Pushes `element` into the queue.
+    ///
+    /// Element is delivered to all current subscribers and retained in history.
+    /// If history is full the oldest element is pushed out of it.
+    pub fn push(&self, element: T) {
+        let mut state = self.state.borrow_mut();
+        let mut wakers = Vec::new();
+        state.subscribers.retain(|subscriber| {
+            if let Some(subscriber) = subscriber.upgrade() {
+                let mut subscriber = subscriber.borrow_mut();
+                subscriber.buffer.push_back(element.clone());
+                wakers.extend(subscriber.waker.take());
+                true
+            } else {
+                false
+            }
+        });
+        state.history.push_back(element);
+        if state.history.len() > self.capacity {
+            state.history.pop_front();
+        }
+        drop(state);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Creates stream yielding retained history followed by newly pushed elements.
+    ///
+    /// Stream terminates once the queue is dropped and all already
+    /// received elements are consumed.
+    pub fn subscribe(&self) -> ReplayStream<T> {
+        let mut state = self.state.borrow_mut();
+        let subscriber = Rc::new(RefCell::new(Subscriber {
+            buffer: state.history.clone(),
+            waker: None,
+            closed: false,
+        }));
+        state.subscribers.push(Rc::downgrade(&subscriber));
+        ReplayStream { subscriber }
+    }
+
+    /// Returns count of elements currently retained in history.
+    pub fn len(&self) -> usize {
+        self.state.borrow().history.len()
+    }
+
+    /// Returns `true` if history is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.state.borrow().history.is_empty()
+    }
+}
+
+impl<T> Drop for ReplayQueue<T> {
+    fn drop(&mut self) {
+        for subscriber in self.state.get_mut().subscribers.drain(..) {
+            if let Some(subscriber) = subscriber.upgrade() {
+                let mut subscriber = subscriber.borrow_mut();
+                subscriber.closed = true;
+                if let Some(waker) = subscriber.waker.take() {
+                    waker.wake();
                 }
             }
         }
     }
 }
 
-impl<'a, T> FusedFuture for Pop<'a, T> {
+/// Stream returned by [subscribe] method.
+///
+/// [subscribe]: ReplayQueue::subscribe
+pub struct ReplayStream<T> {
+    subscriber: Rc<RefCell<Subscriber<T>>>,
+}
+
+impl<T> Stream for ReplayStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut subscriber = self.subscriber.borrow_mut();
+        if let Some(element) = subscriber.buffer.pop_front() {
+            Poll::Ready(Some(element))
+        } else if subscriber.closed {
+            Poll::Ready(None)
+        } else {
+            subscriber.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> FusedStream for ReplayStream<T> {
+    fn is_terminated(&self) -> bool {
+        let subscriber = self.subscriber.borrow();
+        subscriber.closed && subscriber.buffer.is_empty()
+    }
+}
+
+/// Channel without any buffering - elements are handed off directly
+/// from producers to consumers.
+///
+/// [`push`] resolves only once a consumer received the element, channel
+/// itself never holds on to elements - if [`RendezvousPush`] future is
+/// dropped before that, its element is dropped along with it.
+///
+/// [`push`]: Rendezvous::push
+pub struct Rendezvous<T> {
+    state: RefCell<RendezvousState<T>>,
+}
+
+struct RendezvousState<T> {
+    offers: VecDeque<Rc<RefCell<Offer<T>>>>,
+    consumers: Vec<Waker>,
+    closed: bool,
+}
+
+/// Element offered by a pending [`RendezvousPush`].
+struct Offer<T> {
+    element: Option<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> Rendezvous<T> {
+    /// Creates new rendezvous channel.
+    pub fn new() -> Self {
+        Rendezvous {
+            state: RefCell::new(RendezvousState {
+                offers: VecDeque::new(),
+                consumers: Vec::new(),
+                closed: false,
+            }),
+        }
+    }
+
+    /// Pushes (asynchronously) `element` into the channel.
+    ///
+    /// `await` will wait till a consumer receives `element`.
+    ///
+    /// Returns `element` back if channel is (or gets) closed.
+    #[must_use]
+    pub fn push(&self, element: T) -> RendezvousPush<'_, T> {
+        RendezvousPush {
+            channel: self,
+            element: Some(element),
+            offer: None,
+        }
+    }
+
+    /// Pops (asynchronously) element off the channel.
+    ///
+    /// `await` will wait till a producer pushes an element.
+    ///
+    /// Note that popping from a closed channel never completes,
+    /// use [`stream`] to detect closing.
+    ///
+    /// [`stream`]: Rendezvous::stream
+    #[must_use]
+    pub fn pop(&self) -> RendezvousPop<'_, T> {
+        RendezvousPop {
+            channel: self,
+            terminated: false,
+        }
+    }
+
+    /// Pops element off the channel.
+    ///
+    /// Returns `None` if no producer is currently waiting in [`push`].
+    ///
+    /// [`push`]: Rendezvous::push
+    pub fn try_pop(&self) -> Option<T> {
+        let offer = self.state.borrow_mut().offers.pop_front()?;
+        let (element, waker) = {
+            let mut offer = offer.borrow_mut();
+            (offer.element.take(), offer.waker.take())
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        element
+    }
+
+    /// Creates stream of elements popped off the channel.
+    ///
+    /// Stream terminates once channel is closed.
+    pub fn stream(&self) -> RendezvousStream<'_, T> {
+        RendezvousStream {
+            channel: self,
+            terminated: false,
+        }
+    }
+
+    /// Closes the channel.
+    ///
+    /// Pending and future pushes return their elements back.
+    pub fn close(&self) {
+        let (offers, consumers) = {
+            let mut state = self.state.borrow_mut();
+            state.closed = true;
+            (
+                std::mem::take(&mut state.offers),
+                std::mem::take(&mut state.consumers),
+            )
+        };
+        for offer in offers {
+            let waker = offer.borrow_mut().waker.take();
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+        consumers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Returns `true` if channel was closed.
+    pub fn is_closed(&self) -> bool {
+        self.state.borrow().closed
+    }
+
+    /// Returns `None` once channel is closed.
+    fn poll_pop(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if let Some(element) = self.try_pop() {
+            return Poll::Ready(Some(element));
+        }
+        let mut state = self.state.borrow_mut();
+        if state.closed {
+            Poll::Ready(None)
+        } else {
+            if !state
+                .consumers
+                .iter()
+                .any(|waker| waker.will_wake(cx.waker()))
+            {
+                state.consumers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Default for Rendezvous<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Future returned by [push] method.
+///
+/// [push]: Rendezvous::push
+pub struct RendezvousPush<'a, T> {
+    channel: &'a Rendezvous<T>,
+    element: Option<T>,
+    offer: Option<Rc<RefCell<Offer<T>>>>,
+}
+
+impl<'a, T> Unpin for RendezvousPush<'a, T> {}
+
+impl<'a, T> Future for RendezvousPush<'a, T> {
+    type Output = Result<(), T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(offer) = self.offer.clone() {
+            let mut offer = offer.borrow_mut();
+            if offer.element.is_none() {
+                drop(offer);
+                self.offer = None;
+                Poll::Ready(Ok(()))
+            } else if self.channel.is_closed() {
+                let element = offer.element.take().unwrap();
+                drop(offer);
+                self.offer = None;
+                Poll::Ready(Err(element))
+            } else {
+                offer.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        } else if let Some(element) = self.element.take() {
+            if self.channel.is_closed() {
+                return Poll::Ready(Err(element));
+            }
+            let offer = Rc::new(RefCell::new(Offer {
+                element: Some(element),
+                waker: Some(cx.waker().clone()),
+            }));
+            let consumers = {
+                let mut state = self.channel.state.borrow_mut();
+                state.offers.push_back(offer.clone());
+                std::mem::take(&mut state.consumers)
+            };
+            self.offer = Some(offer);
+            consumers.into_iter().for_each(Waker::wake);
+            Poll::Pending
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a, T> Drop for RendezvousPush<'a, T> {
+    fn drop(&mut self) {
+        // Withdraw own offer (and its element) if it wasn't received yet
+        if let Some(offer) = self.offer.take() {
+            self.channel
+                .state
+                .borrow_mut()
+                .offers
+                .retain(|other| !Rc::ptr_eq(other, &offer));
+        }
+    }
+}
+
+impl<'a, T> FusedFuture for RendezvousPush<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.element.is_none() && self.offer.is_none()
+    }
+}
+
+/// Future returned by [pop] method.
+///
+/// [pop]: Rendezvous::pop
+pub struct RendezvousPop<'a, T> {
+    channel: &'a Rendezvous<T>,
+    terminated: bool,
+}
+
+impl<'a, T> Unpin for RendezvousPop<'a, T> {}
+
+impl<'a, T> Future for RendezvousPop<'a, T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.terminated {
+            return Poll::Pending;
+        }
+        match self.channel.poll_pop(cx) {
+            Poll::Ready(Some(element)) => {
+                self.terminated = true;
+                Poll::Ready(element)
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<'a, T> FusedFuture for RendezvousPop<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+/// Stream returned by [stream] method.
+///
+/// [stream]: Rendezvous::stream
+pub struct RendezvousStream<'a, T> {
+    channel: &'a Rendezvous<T>,
+    terminated: bool,
+}
+
+impl<'a, T> Unpin for RendezvousStream<'a, T> {}
+
+impl<'a, T> Stream for RendezvousStream<'a, T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        let result = self.channel.poll_pop(cx);
+        self.terminated = matches!(result, Poll::Ready(None));
+        result
+    }
+}
+
+impl<'a, T> FusedStream for RendezvousStream<'a, T> {
     fn is_terminated(&self) -> bool {
         self.terminated
     }
@@ -203,12 +1095,44 @@ impl<'a, T> FusedFuture for Pop<'a, T> {
 
 #[cfg(test)]
 mod tests {
-    use std::{rc::Rc, time::Duration};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+        time::Duration,
+    };
 
-    use futures::{join, FutureExt};
+    use futures::{join, FutureExt, StreamExt};
     use wasm_bindgen_test::wasm_bindgen_test;
 
-    use crate::{sleep, spawn, Queue};
+    use crate::{
+        queue::{merge_by, spawn_workers, Rendezvous, ReplayQueue},
+        sleep, spawn, Queue,
+    };
+
+    thread_local! {
+        static HOOK: RefCell<Option<Box<dyn FnMut()>>> = RefCell::new(None);
+        static WAKES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Waker synchronously running `HOOK` when woken.
+    struct HookWaker;
+
+    impl Wake for HookWaker {
+        fn wake(self: Arc<Self>) {
+            WAKES.with(|wakes| wakes.set(wakes.get() + 1));
+            if let Some(mut hook) = HOOK.with(|hook| hook.borrow_mut().take()) {
+                hook();
+                HOOK.with(|slot| *slot.borrow_mut() = Some(hook));
+            }
+        }
+    }
+
+    fn set_hook(hook: impl FnMut() + 'static) {
+        WAKES.with(|wakes| wakes.set(0));
+        HOOK.with(|slot| *slot.borrow_mut() = Some(Box::new(hook)));
+    }
 
     #[wasm_bindgen_test]
     async fn test_unbounded() {
@@ -287,4 +1211,393 @@ mod tests {
         assert_eq!(queue.len(), 3);
         assert!(queue.is_full());
     }
+
+    #[wasm_bindgen_test]
+    async fn test_replay() {
+        let queue = ReplayQueue::with_capacity(2);
+        assert!(queue.is_empty());
+
+        queue.push(1);
+        queue.push(2);
+        queue.push(3);
+        assert_eq!(queue.len(), 2);
+
+        let mut early = queue.subscribe();
+        assert_eq!(early.next().await, Some(2));
+        assert_eq!(early.next().await, Some(3));
+        assert_eq!(early.next().now_or_never(), None);
+
+        queue.push(4);
+        let late = queue.subscribe();
+        queue.push(5);
+        drop(queue);
+
+        assert_eq!(early.collect::<Vec<_>>().await, vec![4, 5]);
+        assert_eq!(late.collect::<Vec<_>>().await, vec![3, 4, 5]);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_drop_during_wake() {
+        let queue: &'static Queue<i32> = Box::leak(Box::new(Queue::new()));
+        let waker = Waker::from(Arc::new(HookWaker));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut first = queue.pop();
+        let mut second = queue.pop();
+        assert!(first.poll_unpin(&mut cx).is_pending());
+        assert!(second.poll_unpin(&mut cx).is_pending());
+
+        let pops = Rc::new(RefCell::new(vec![first, second]));
+        let pops_clone = pops.clone();
+        set_hook(move || {
+            assert_eq!(queue.try_pop(), Some(1));
+            pops_clone.borrow_mut().clear();
+        });
+        queue.push(1);
+
+        assert_eq!(WAKES.with(Cell::get), 1);
+        assert!(pops.borrow().is_empty());
+        assert!(queue.is_empty());
+
+        let mut third = queue.pop();
+        assert!(third.poll_unpin(&mut cx).is_pending());
+        set_hook(|| {});
+        queue.push(2);
+        assert_eq!(WAKES.with(Cell::get), 1);
+        assert_eq!(third.poll_unpin(&mut cx), Poll::Ready(2));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_poll_during_wake() {
+        let queue: &'static Queue<i32> = Box::leak(Box::new(Queue::new()));
+        let waker = Waker::from(Arc::new(HookWaker));
+
+        let pop = Rc::new(RefCell::new(queue.pop()));
+        let result = Rc::new(Cell::new(None));
+        let (pop_clone, result_clone) = (pop.clone(), result.clone());
+        set_hook(move || {
+            let waker = Waker::from(Arc::new(HookWaker));
+            let mut cx = Context::from_waker(&waker);
+            if let Poll::Ready(value) = pop_clone.borrow_mut().poll_unpin(&mut cx) {
+                result_clone.set(Some(value));
+            }
+        });
+        assert!(pop
+            .borrow_mut()
+            .poll_unpin(&mut Context::from_waker(&waker))
+            .is_pending());
+
+        queue.push(1);
+        assert_eq!(result.get(), Some(1));
+        assert!(queue.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_stream_map() {
+        let queue = Queue::new();
+        queue.push(1);
+        queue.push(2);
+
+        let mut stream = queue.stream_map(|element| element * 10);
+        assert_eq!(stream.next().await, Some(10));
+        queue.push(3);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(stream.next().await, Some(20));
+        assert_eq!(stream.next().await, Some(30));
+        assert!(queue.is_empty());
+        assert_eq!(stream.next().now_or_never(), None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_close() {
+        let queue = Queue::with_capacity(2);
+        assert!(!queue.is_closed());
+
+        assert_eq!(queue.try_push(1), Ok(()));
+        assert_eq!(queue.try_push(2), Ok(()));
+        assert_eq!(queue.try_push(3), Err(3));
+        queue.push(3);
+        assert_eq!(queue.try_pop(), Some(2));
+
+        let queue = Rc::new(queue);
+        let queue_clone = queue.clone();
+        let task = spawn(async move { queue_clone.stream().collect::<Vec<_>>().await });
+        sleep(Duration::from_millis(100)).await;
+        queue.push(4);
+        queue.close();
+        assert!(queue.is_closed());
+
+        assert_eq!(queue.try_push(5), Err(5));
+        queue.push(6);
+        assert_eq!(task.await.unwrap(), vec![3, 4]);
+        assert!(queue.is_empty());
+
+        let queue = Queue::new();
+        queue.push(1);
+        queue.close();
+        assert_eq!(queue.pop().await, 1);
+        assert_eq!(queue.stream().next().await, None);
+        assert_eq!(queue.pop().now_or_never(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_downgrade() {
+        let queue = Rc::new(Queue::new());
+        let weak = queue.downgrade();
+
+        weak.push(1);
+        assert_eq!(queue.try_pop(), Some(1));
+        assert!(weak.upgrade().is_some());
+
+        drop(queue);
+        weak.push(2);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_merge_by() {
+        let first = Rc::new(Queue::new());
+        let second = Rc::new(Queue::new());
+        let merged = merge_by(vec![first.clone(), second.clone()], |(time, _)| *time);
+
+        first.push((1, "a"));
+        first.push((4, "b"));
+        second.push((2, "c"));
+        second.push((3, "d"));
+        first.close();
+
+        let queue = second.clone();
+        spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            queue.push((5, "e"));
+            queue.close();
+        });
+
+        assert_eq!(
+            merged.map(|(_, name)| name).collect::<Vec<_>>().await,
+            vec!["a", "c", "d", "b", "e"]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_push_backpressured() {
+        let queue = Rc::new(Queue::with_capacity(1));
+        assert_eq!(queue.push_backpressured(1).await, Ok(()));
+
+        let queue_clone = queue.clone();
+        let task = spawn(async move {
+            queue_clone.push_backpressured(2).await.unwrap();
+            queue_clone.push_backpressured(3).await
+        });
+        sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+        assert_eq!(queue.len(), 1);
+
+        assert_eq!(queue.pop().await, 1);
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(queue.try_pop(), Some(2));
+        queue.close();
+        assert_eq!(task.await.unwrap(), Err(3));
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_wait_until_empty() {
+        let queue = Rc::new(Queue::new());
+        queue.wait_until_empty().await;
+
+        queue.push(1);
+        queue.push(2);
+        queue.push(3);
+        let queue_clone = queue.clone();
+        let task = spawn(async move { queue_clone.wait_until_empty().await });
+        sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+
+        assert_eq!(queue.pop().await, 1);
+        assert_eq!(queue.try_pop(), Some(2));
+        sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+
+        assert_eq!(queue.drain(), vec![3]);
+        task.await.unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_fold() {
+        let queue = Rc::new(Queue::new());
+        let queue_clone = queue.clone();
+        let sum = spawn(async move { queue_clone.fold(0, |sum, element| sum + element).await });
+        for i in 1..=4 {
+            queue.push(i);
+        }
+        sleep(Duration::from_millis(100)).await;
+        queue.push(5);
+        queue.close();
+        assert_eq!(sum.await.unwrap(), 15);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_into_vec() {
+        let queue = Queue::with_capacity(3);
+        for i in 1..=4 {
+            queue.push(i);
+        }
+        assert_eq!(queue.try_pop(), Some(2));
+        assert_eq!(queue.into_vec(), vec![3, 4]);
+
+        let queue = Rc::new(Queue::new());
+        queue.push("a");
+        let queue = Rc::try_unwrap(queue).ok().unwrap();
+        assert_eq!(queue.into_vec(), vec!["a"]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_rendezvous() {
+        let channel = Rc::new(Queue::rendezvous());
+        assert_eq!(channel.try_pop(), None);
+
+        let channel_clone = channel.clone();
+        let producer = spawn(async move { channel_clone.push(1).await });
+        sleep(Duration::from_millis(100)).await;
+        assert!(!producer.is_finished());
+        assert_eq!(channel.pop().await, 1);
+        assert_eq!(producer.await.unwrap(), Ok(()));
+
+        let channel_clone = channel.clone();
+        let consumer = spawn(async move { channel_clone.pop().await });
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(channel.push(2).await, Ok(()));
+        assert_eq!(consumer.await.unwrap(), 2);
+
+        let channel_clone = channel.clone();
+        let producer = spawn(async move { channel_clone.push(3).await });
+        sleep(Duration::from_millis(100)).await;
+        channel.close();
+        assert_eq!(producer.await.unwrap(), Err(3));
+        assert_eq!(channel.push(4).await, Err(4));
+        assert_eq!(channel.stream().next().await, None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rendezvous_push_drop() {
+        let channel = Rendezvous::new();
+        let waker = Arc::new(HookWaker).into();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut first = channel.push(1);
+        assert!(first.poll_unpin(&mut cx).is_pending());
+        assert_eq!(channel.try_pop(), Some(1));
+        let mut second = channel.push(2);
+        assert!(second.poll_unpin(&mut cx).is_pending());
+        drop(first);
+        assert_eq!(channel.try_pop(), Some(2));
+        assert_eq!(second.poll_unpin(&mut cx), Poll::Ready(Ok(())));
+
+        let mut third = channel.push(3);
+        assert!(third.poll_unpin(&mut cx).is_pending());
+        drop(third);
+        assert_eq!(channel.try_pop(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_poll_push_ready() {
+        let queue = Queue::with_capacity(1);
+        let waker = Arc::new(HookWaker).into();
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(queue.poll_push_ready(&mut cx).is_ready());
+        assert_eq!(queue.push_now(1), Ok(()));
+        assert!(queue.poll_push_ready(&mut cx).is_pending());
+        assert_eq!(queue.try_pop(), Some(1));
+        assert!(queue.poll_push_ready(&mut cx).is_ready());
+
+        queue.close();
+        assert!(queue.poll_push_ready(&mut cx).is_ready());
+        assert_eq!(queue.push_now(2), Err(2));
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_spawn_workers() {
+        let queue = Rc::new(Queue::new());
+        let (running, peak, done) = (
+            Rc::new(Cell::new(0)),
+            Rc::new(Cell::new(0)),
+            Rc::new(RefCell::new(vec![])),
+        );
+        let (running_clone, peak_clone, done_clone) = (running.clone(), peak.clone(), done.clone());
+        let workers = spawn_workers(queue.clone(), 2, move |element| {
+            let (running, peak, done) = (
+                running_clone.clone(),
+                peak_clone.clone(),
+                done_clone.clone(),
+            );
+            async move {
+                running.set(running.get() + 1);
+                peak.set(peak.get().max(running.get()));
+                sleep(Duration::from_millis(20)).await;
+                running.set(running.get() - 1);
+                done.borrow_mut().push(element);
+            }
+        });
+        for i in 0..5 {
+            queue.push(i);
+        }
+        sleep(Duration::from_millis(30)).await;
+        assert_eq!(peak.get(), 2);
+        queue.close();
+        workers.await.unwrap();
+
+        let mut done = done.borrow().clone();
+        done.sort();
+        assert_eq!(done, vec![0, 1, 2, 3, 4]);
+        assert_eq!(peak.get(), 2);
+        assert_eq!(running.get(), 0);
+    }
+
+    /// Plain tests run on host target - see `test.sh`.
+    #[cfg(all(feature = "dev", not(target_arch = "wasm32")))]
+    mod host {
+        use std::task::Poll;
+
+        use crate::{
+            queue::Rendezvous,
+            spawn::{poll_once, run_until_stalled},
+            Queue,
+        };
+
+        #[test]
+        fn test_pop() {
+            let queue = Queue::new();
+            let mut pop = queue.pop();
+            assert_eq!(poll_once(&mut pop), Poll::Pending);
+            queue.push(1);
+            assert_eq!(poll_once(&mut pop), Poll::Ready(1));
+        }
+
+        #[test]
+        fn test_push_backpressured() {
+            let queue = Queue::with_capacity(1);
+            queue.push(1);
+            let mut push = queue.push_backpressured(2);
+            assert_eq!(poll_once(&mut push), Poll::Pending);
+            assert_eq!(queue.try_pop(), Some(1));
+            assert_eq!(run_until_stalled(&mut push), Poll::Ready(Ok(())));
+            assert_eq!(queue.try_pop(), Some(2));
+
+            queue.close();
+            let mut push = queue.push_backpressured(3);
+            assert_eq!(poll_once(&mut push), Poll::Ready(Err(3)));
+        }
+
+        #[test]
+        fn test_rendezvous() {
+            let channel = Rendezvous::new();
+            let mut pop = channel.pop();
+            assert_eq!(poll_once(&mut pop), Poll::Pending);
+            let mut push = channel.push(1);
+            assert_eq!(poll_once(&mut push), Poll::Pending);
+            assert_eq!(poll_once(&mut pop), Poll::Ready(1));
+            assert_eq!(poll_once(&mut push), Poll::Ready(Ok(())));
+        }
+    }
 }
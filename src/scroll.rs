@@ -0,0 +1,77 @@
+//! Scroll-related utilities.
+
+#[cfg(feature = "event")]
+use std::rc::Rc;
+
+#[cfg(feature = "event")]
+use web_sys::{Event, Window};
+use web_sys::{ScrollBehavior, ScrollToOptions};
+
+#[cfg(feature = "event")]
+use crate::event::{listen_raf_throttled_filter_map, EventStream};
+use crate::{window, JsError};
+
+/// Gets window's current scroll position as `(x, y)` pair (in pixels).
+pub fn scroll_position() -> Result<(f64, f64), JsError> {
+    let window = window();
+    Ok((window.scroll_x()?, window.scroll_y()?))
+}
+
+/// Scrolls window to given position (in pixels).
+///
+/// If `smooth` is `true` scrolling is animated.
+pub fn scroll_to(x: f64, y: f64, smooth: bool) {
+    let options = ScrollToOptions::new();
+    options.set_left(x);
+    options.set_top(y);
+    options.set_behavior(if smooth {
+        ScrollBehavior::Smooth
+    } else {
+        ScrollBehavior::Instant
+    });
+    window().scroll_to_with_scroll_to_options(&options);
+}
+
+/// Create stream of window's scroll positions.
+///
+/// Stream yields at most one position per animation frame.
+#[cfg(feature = "event")]
+pub fn on_scroll() -> Result<EventStream<Window, (f64, f64), Event>, JsError> {
+    listen_raf_throttled_filter_map(&Rc::new(window()), "scroll", |_: Event| {
+        scroll_position().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "event")]
+    use futures::StreamExt;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[cfg(feature = "event")]
+    use crate::scroll::on_scroll;
+    use crate::{
+        body,
+        scroll::{scroll_position, scroll_to},
+    };
+
+    #[wasm_bindgen_test]
+    fn test_scroll_to() {
+        body().style().set_property("height", "10000px").unwrap();
+        scroll_to(0.0, 100.0, false);
+        assert_eq!(scroll_position().unwrap(), (0.0, 100.0));
+        scroll_to(0.0, 0.0, false);
+        body().style().remove_property("height").unwrap();
+    }
+
+    #[cfg(feature = "event")]
+    #[wasm_bindgen_test]
+    async fn test_on_scroll() {
+        body().style().set_property("height", "10000px").unwrap();
+        let mut positions = on_scroll().unwrap();
+        scroll_to(0.0, 200.0, false);
+        assert_eq!(positions.next().await, Some((0.0, 200.0)));
+        scroll_to(0.0, 0.0, false);
+        body().style().remove_property("height").unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 //! Sleeping.
 
-use futures::{Future, FutureExt};
+use futures::{stream::FusedStream, Future, FutureExt, Stream};
 use std::{
+    ops::Range,
     pin::Pin,
     task::{Context, Poll},
     time::Duration,
@@ -9,6 +10,8 @@ use std::{
 use zduny_wasm_timer::Delay;
 pub use zduny_wasm_timer::Instant;
 
+pub use crate::error::Elapsed;
+
 /// Waits until `duration` has elapsed.
 ///
 /// An asynchronous analog to [`std::thread::sleep`].
@@ -23,14 +26,26 @@ pub fn sleep_until(deadline: Instant) -> Sleep {
     Sleep {
         deadline,
         delay: Delay::new_at(deadline),
+        elapsed: false,
     }
 }
 
-/// Future returned by [`sleep`] and [`sleep_until`].
+/// Waits for a random duration picked uniformly from `range`.
+///
+/// Useful for spreading out periodic work across clients.
+/// If `range` is empty it waits for `range.start`.
+#[must_use]
+pub fn sleep_random_in(range: Range<Duration>) -> Sleep {
+    let span = range.end.saturating_sub(range.start);
+    sleep(range.start + span.mul_f64(js_sys::Math::random()))
+}
+
+/// Future returned by [`sleep`], [`sleep_until`] and [`sleep_random_in`].
 #[derive(Debug)]
 pub struct Sleep {
     deadline: Instant,
     delay: Delay,
+    elapsed: bool,
 }
 
 impl Sleep {
@@ -41,9 +56,10 @@ impl Sleep {
 
     /// Returns `true` if `Sleep` has elapsed.
     ///
-    /// A `Sleep` instance is elapsed when the requested duration has elapsed.
+    /// A `Sleep` instance is elapsed when the requested duration has elapsed
+    /// or when the future has already completed.
     pub fn is_elapsed(&self) -> bool {
-        Instant::now() > self.deadline
+        self.elapsed || Instant::now() >= self.deadline
     }
 
     /// Resets the `Sleep` instance to a new deadline.
@@ -54,6 +70,8 @@ impl Sleep {
     /// This function can be called both before and after the future has
     /// completed.
     pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+        self.elapsed = false;
         self.delay.reset_at(deadline);
     }
 }
@@ -63,18 +81,169 @@ impl Future for Sleep {
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match self.delay.poll_unpin(cx) {
-            Poll::Ready(_) => Poll::Ready(()),
+            Poll::Ready(_) => {
+                self.elapsed = true;
+                Poll::Ready(())
+            }
             Poll::Pending => Poll::Pending,
         }
     }
 }
 
+/// Creates new [`Interval`] yielding every `period`, starting after
+/// the first `period` has elapsed.
+///
+/// `period` must be greater than 0 - it'll panic otherwise.
+#[must_use]
+pub fn interval(period: Duration) -> Interval {
+    assert!(period > Duration::ZERO, "period must be greater than 0");
+    let deadline = Instant::now() + period;
+    Interval {
+        deadline,
+        period,
+        delay: Some(Delay::new_at(deadline)),
+    }
+}
+
+/// Creates new [`Interval`] yielding every `period`, with ticks aligned
+/// to wall-clock boundaries (multiples of `period` since Unix epoch).
+///
+/// For example with `period` of one second ticks land at the start of each
+/// second. Useful for clock displays.
+///
+/// `period` must be greater than 0 - it'll panic otherwise.
+#[must_use]
+pub fn interval_aligned(period: Duration) -> Interval {
+    assert!(period > Duration::ZERO, "period must be greater than 0");
+    let deadline = Instant::now() + until_boundary(js_sys::Date::now(), period);
+    Interval {
+        deadline,
+        period,
+        delay: Some(Delay::new_at(deadline)),
+    }
+}
+
+/// Returns time from `now` (milliseconds since Unix epoch) to the next
+/// multiple of `period`.
+fn until_boundary(now: f64, period: Duration) -> Duration {
+    let period = period.as_secs_f64() * 1000.0;
+    Duration::from_secs_f64((period - now % period) / 1000.0)
+}
+
+/// Stream returned by [`interval`] and [`interval_aligned`].
+#[derive(Debug)]
+pub struct Interval {
+    deadline: Instant,
+    period: Duration,
+    delay: Option<Delay>,
+}
+
+impl Interval {
+    /// Returns the period of the interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Stops the interval.
+    ///
+    /// Pending timer is dropped (and therefore cleared) right away and
+    /// the stream terminates - subsequent polls return `None`.
+    pub fn stop(&mut self) {
+        self.delay = None;
+    }
+
+    /// Returns `true` if interval was stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.delay.is_none()
+    }
+}
+
+impl Stream for Interval {
+    type Item = ();
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(delay) = self.delay.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match delay.poll_unpin(cx) {
+            Poll::Ready(_) => {
+                let deadline = self.deadline + self.period;
+                self.deadline = deadline;
+                if let Some(delay) = self.delay.as_mut() {
+                    delay.reset_at(deadline);
+                }
+                Poll::Ready(Some(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl FusedStream for Interval {
+    fn is_terminated(&self) -> bool {
+        self.is_stopped()
+    }
+}
+
+/// Reusable deadline guard checked synchronously.
+///
+/// Useful for threading a single deadline through several checkpoints
+/// of a larger operation.
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout {
+    deadline: Instant,
+}
+
+impl Timeout {
+    /// Starts timeout elapsing after `duration`.
+    pub fn start(duration: Duration) -> Self {
+        Timeout {
+            deadline: Instant::now() + duration,
+        }
+    }
+
+    /// Returns [`Elapsed`] error if timeout has elapsed.
+    pub fn check(&self) -> Result<(), Elapsed> {
+        if Instant::now() >= self.deadline {
+            Err(Elapsed(()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Restarts timeout so it elapses after `duration` from now.
+    pub fn reset(&mut self, duration: Duration) {
+        self.deadline = Instant::now() + duration;
+    }
+
+    /// Returns time remaining until timeout elapses.
+    ///
+    /// Returns zero duration if timeout has already elapsed.
+    pub fn remaining(&self) -> Duration {
+        let now = Instant::now();
+        if now >= self.deadline {
+            Duration::ZERO
+        } else {
+            self.deadline - now
+        }
+    }
+
+    /// Returns the instant at which timeout elapses.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use futures::StreamExt;
     use std::time::Duration;
     use wasm_bindgen_test::wasm_bindgen_test;
 
-    use crate::{sleep, sleep::Instant};
+    use crate::{
+        sleep,
+        sleep::{interval, interval_aligned, sleep_random_in, until_boundary, Instant, Timeout},
+    };
 
     #[wasm_bindgen_test]
     async fn test_sleep() {
@@ -83,4 +252,76 @@ mod tests {
         let difference = Instant::now() - current;
         assert!(difference.as_secs_f64() >= 1.0)
     }
+
+    #[wasm_bindgen_test]
+    async fn test_is_elapsed_after_completion() {
+        let mut sleep = sleep(Duration::from_millis(100));
+        assert!(!sleep.is_elapsed());
+        (&mut sleep).await;
+        assert!(sleep.is_elapsed());
+
+        sleep.reset(Instant::now() + Duration::from_secs(10));
+        assert!(!sleep.is_elapsed());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_sleep_random_in() {
+        let current = Instant::now();
+        let sleep = sleep_random_in(Duration::from_millis(100)..Duration::from_millis(200));
+        assert!(sleep.deadline() >= current + Duration::from_millis(100));
+        assert!(sleep.deadline() < Instant::now() + Duration::from_millis(200));
+
+        sleep.await;
+        let difference = Instant::now() - current;
+        assert!(difference.as_secs_f64() >= 0.1)
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_timeout() {
+        let mut timeout = Timeout::start(Duration::from_millis(100));
+        assert!(timeout.check().is_ok());
+        assert!(timeout.remaining() > Duration::ZERO);
+        assert!(timeout.remaining() <= Duration::from_millis(100));
+
+        sleep(Duration::from_millis(150)).await;
+        assert!(timeout.check().is_err());
+        assert_eq!(timeout.remaining(), Duration::ZERO);
+
+        timeout.reset(Duration::from_secs(10));
+        assert!(timeout.check().is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_interval_stop() {
+        let current = Instant::now();
+        let mut interval = interval(Duration::from_millis(100));
+        interval.next().await.unwrap();
+        interval.next().await.unwrap();
+        let difference = Instant::now() - current;
+        assert!(difference.as_secs_f64() >= 0.2);
+
+        interval.stop();
+        assert!(interval.is_stopped());
+        assert_eq!(interval.next().await, None);
+        assert_eq!(interval.next().await, None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_until_boundary() {
+        let second = Duration::from_secs(1);
+        assert_eq!(until_boundary(10_250.0, second), Duration::from_millis(750));
+        assert_eq!(until_boundary(10_000.0, second), second);
+        assert_eq!(
+            until_boundary(90_000.0, Duration::from_secs(60)),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_interval_aligned() {
+        let mut interval = interval_aligned(Duration::from_millis(500));
+        interval.next().await.unwrap();
+        let offset = js_sys::Date::now() % 500.0;
+        assert!(offset < 50.0);
+    }
 }
@@ -1,35 +1,415 @@
 //! Background task spawning.
 
 use futures::Future;
-use std::rc::Rc;
+use std::cell::RefCell;
+use std::fmt::{self, Debug};
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
 use std::sync::Mutex;
-use std::task::{Poll, Waker};
+use std::task::{Context, Poll, Waker};
+use std::thread::LocalKey;
+#[cfg(feature = "sleep")]
+use std::time::Duration;
+#[cfg(feature = "dev")]
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::Wake,
+};
+
+#[cfg(feature = "queue")]
+use crate::queue::Queue;
+#[cfg(feature = "sleep")]
+use crate::sleep::sleep;
+use futures::{future, stream::FuturesUnordered, StreamExt};
+#[cfg(feature = "queue")]
+use futures::{stream::LocalBoxStream, Stream};
+
+thread_local! {
+    static GLOBAL: Runtime = Runtime::new();
+}
 
 /// Spawns a new asynchronous task, returning a [`JoinHandle`] for it.
+///
+/// Task is tracked by the [global] runtime.
+///
+/// [global]: Runtime::global
 pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
 where
     F: Future + 'static,
     F::Output: 'static,
 {
-    let join_handle = JoinHandle::new();
-    let join_handle_clone = join_handle.clone();
-    wasm_bindgen_futures::spawn_local(async move {
-        join_handle_clone.set_result(future.await);
+    GLOBAL.with(|runtime| runtime.spawn(future))
+}
+
+/// Spawns a new asynchronous task, returning a [`JoinHandle`] for it.
+///
+/// If the task is aborted before completing (even before it is first polled),
+/// future returned by `cleanup` is spawned as a separate task.
+pub fn spawn_with_cleanup<F, C, Fut>(future: F, cleanup: C) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+    C: FnOnce() -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let mut guard = CleanupGuard {
+        cleanup: Some(cleanup),
+    };
+    spawn(async move {
+        let output = future.await;
+        guard.disarm();
+        output
+    })
+}
+
+/// Spawns `cleanup` future on drop unless disarmed.
+struct CleanupGuard<C, Fut>
+where
+    C: FnOnce() -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    cleanup: Option<C>,
+}
+
+impl<C, Fut> CleanupGuard<C, Fut>
+where
+    C: FnOnce() -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    fn disarm(&mut self) {
+        self.cleanup = None;
+    }
+}
+
+impl<C, Fut> Drop for CleanupGuard<C, Fut>
+where
+    C: FnOnce() -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            spawn(cleanup());
+        }
+    }
+}
+
+/// Spawns a new asynchronous task, invoking `on_complete` with its result.
+///
+/// Useful for starting asynchronous work from synchronous code (e.g. event
+/// handlers).
+///
+/// See [`Runtime::spawn_then`].
+pub fn spawn_then<F, C>(future: F, on_complete: C)
+where
+    F: Future + 'static,
+    F::Output: 'static,
+    C: FnOnce(Result<F::Output, JoinError>) + 'static,
+{
+    GLOBAL.with(|runtime| runtime.spawn_then(future, on_complete))
+}
+
+/// Spawns a batch of asynchronous tasks, returning a [`JoinHandle`] for each.
+///
+/// See [`Runtime::spawn_batch`].
+pub fn spawn_batch<F>(futures: Vec<F>) -> Vec<JoinHandle<F::Output>>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    GLOBAL.with(|runtime| runtime.spawn_batch(futures))
+}
+
+/// Spawns a new asynchronous task that is aborted if it doesn't complete
+/// within `duration`, returning a [`JoinHandle`] for it.
+///
+/// Awaiting a task that exceeded its deadline fails with a [timeout] [`JoinError`].
+///
+/// [timeout]: method@crate::spawn::JoinError::is_timeout
+#[cfg(feature = "sleep")]
+pub fn spawn_with_deadline<F>(duration: Duration, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    GLOBAL.with(|runtime| runtime.spawn_task(future, Some(Box::pin(sleep(duration)))))
+}
+
+/// Spawns a new asynchronous task producing a sequence of values,
+/// returning a [`Stream`] of them.
+///
+/// Task yields values with [`Yielder::yield_value`], which waits until
+/// the value is consumed. Task is aborted when returned stream is dropped.
+#[cfg(feature = "queue")]
+pub fn spawn_stream<T, F, Fut>(f: F) -> SpawnedStream<T>
+where
+    T: 'static,
+    F: FnOnce(Yielder<T>) -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    let queue = Rc::new(Queue::with_capacity(1));
+    let future = f(Yielder {
+        queue: queue.clone(),
+    });
+    let queue_clone = queue.clone();
+    let handle = spawn(async move {
+        future.await;
+        queue_clone.close();
     });
-    join_handle
+    let items = futures::stream::unfold(queue.clone(), |queue| async move {
+        let item = queue.stream().next().await;
+        item.map(|item| (item, queue))
+    })
+    .boxed_local();
+
+    SpawnedStream {
+        queue,
+        items,
+        handle,
+    }
+}
+
+/// Creates new [`Runtime`] - an independent scope of tasks.
+pub fn runtime() -> Runtime {
+    Runtime::new()
+}
+
+/// Handle to a group of tasks that can be inspected and aborted together.
+///
+/// Cloning a `Runtime` creates another handle to the same group of tasks.
+#[derive(Clone, Default)]
+pub struct Runtime {
+    tasks: Rc<RefCell<Vec<Weak<dyn Task>>>>,
+}
+
+impl Runtime {
+    /// Creates new runtime with no tasks.
+    pub fn new() -> Self {
+        Runtime::default()
+    }
+
+    /// Returns handle to the global runtime tracking tasks created with [`spawn`].
+    pub fn global() -> Self {
+        GLOBAL.with(Runtime::clone)
+    }
+
+    /// Spawns a new asynchronous task belonging to this runtime,
+    /// returning a [`JoinHandle`] for it.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.spawn_task(future, None)
+    }
+
+    fn spawn_task<F>(&self, future: F, deadline: Option<Deadline>) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let join_handle = JoinHandle::new();
+        wasm_bindgen_futures::spawn_local(Spawned {
+            future: Box::pin(future),
+            deadline,
+            state: join_handle.state.clone(),
+        });
+        self.track(&join_handle);
+        join_handle
+    }
+
+    /// Spawns a new asynchronous task belonging to this runtime,
+    /// invoking `on_complete` with its result.
+    ///
+    /// `on_complete` is invoked with a [`JoinError`] if the task is aborted
+    /// (with [`abort_all`]).
+    ///
+    /// [`abort_all`]: Runtime::abort_all
+    pub fn spawn_then<F, C>(&self, future: F, on_complete: C)
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+        C: FnOnce(Result<F::Output, JoinError>) + 'static,
+    {
+        let join_handle = self.spawn(future);
+        wasm_bindgen_futures::spawn_local(async move { on_complete(join_handle.await) });
+    }
+
+    /// Spawns a batch of asynchronous tasks belonging to this runtime,
+    /// returning a [`JoinHandle`] for each.
+    ///
+    /// All tasks of the batch are driven by a single executor task, which
+    /// avoids scheduling overhead of spawning each of them separately.
+    ///
+    /// Tasks of the batch are polled cooperatively - a task that blocks
+    /// for long between `await` points delays all the others, and the whole
+    /// batch gets the same share of the executor as a single spawned task.
+    /// Prefer it for many short-lived tasks.
+    pub fn spawn_batch<F>(&self, futures: Vec<F>) -> Vec<JoinHandle<F::Output>>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let batch = FuturesUnordered::new();
+        let join_handles = futures
+            .into_iter()
+            .map(|future| {
+                let join_handle = JoinHandle::new();
+                batch.push(Spawned {
+                    future: Box::pin(future),
+                    deadline: None,
+                    state: join_handle.state.clone(),
+                });
+                self.track(&join_handle);
+                join_handle
+            })
+            .collect();
+        wasm_bindgen_futures::spawn_local(batch.for_each(|()| future::ready(())));
+        join_handles
+    }
+
+    fn track<T: 'static>(&self, join_handle: &JoinHandle<T>) {
+        let mut tasks = self.tasks.borrow_mut();
+        // Finished tasks are pruned lazily, only when list is about to grow.
+        // Reserving room for as many tasks as survived keeps it amortized O(1).
+        if tasks.len() == tasks.capacity() {
+            tasks.retain(is_active);
+            let active = tasks.len();
+            tasks.reserve(active);
+        }
+        let state: Rc<dyn Task> = join_handle.state.clone();
+        tasks.push(Rc::downgrade(&state));
+    }
+
+    /// Aborts all tasks belonging to this runtime.
+    ///
+    /// See [`JoinHandle::abort`].
+    pub fn abort_all(&self) {
+        let tasks: Vec<_> = self
+            .tasks
+            .borrow_mut()
+            .drain(..)
+            .filter_map(|task| task.upgrade())
+            .collect();
+        for task in tasks {
+            task.abort();
+        }
+    }
+
+    /// Returns count of tasks belonging to this runtime that haven't finished yet.
+    pub fn active_count(&self) -> usize {
+        let mut tasks = self.tasks.borrow_mut();
+        tasks.retain(is_active);
+        tasks.len()
+    }
+}
+
+impl Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Runtime")
+            .field("active_count", &self.active_count())
+            .finish()
+    }
+}
+
+type Deadline = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Future actually driven by the executor - stops polling the spawned
+/// future as soon as the task is aborted or its deadline passes.
+struct Spawned<F>
+where
+    F: Future,
+{
+    future: Pin<Box<F>>,
+    deadline: Option<Deadline>,
+    state: Rc<Mutex<State<F::Output>>>,
+}
+
+impl<F> Future for Spawned<F>
+where
+    F: Future,
+{
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.is_finished() {
+                return Poll::Ready(());
+            }
+            state.update_task_waker(cx.waker());
+        }
+        if let Some(deadline) = &mut self.deadline {
+            if deadline.as_mut().poll(cx).is_ready() {
+                let error = JoinError {
+                    kind: JoinErrorKind::Timeout,
+                };
+                self.state.lock().unwrap().set_result(Err(error));
+                return Poll::Ready(());
+            }
+        }
+        match self.future.as_mut().poll(cx) {
+            Poll::Ready(value) => {
+                self.state.lock().unwrap().set_result(Ok(value));
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+trait Task {
+    fn abort(&self);
+
+    fn is_finished(&self) -> bool;
+}
+
+impl<T> Task for Mutex<State<T>> {
+    fn abort(&self) {
+        let error = JoinError {
+            kind: JoinErrorKind::Cancelled,
+        };
+        self.lock().unwrap().set_result(Err(error));
+    }
+
+    fn is_finished(&self) -> bool {
+        self.lock().unwrap().is_finished()
+    }
+}
+
+fn is_active(task: &Weak<dyn Task>) -> bool {
+    task.upgrade().is_some_and(|task| !task.is_finished())
 }
 
 /// Task failed to execute to completion.
 ///
-/// Currently can only be caused by cancellation.
+/// Caused either by cancellation or by the task exceeding its deadline.
 #[derive(Debug)]
 #[non_exhaustive]
-pub struct JoinError {}
+pub struct JoinError {
+    kind: JoinErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoinErrorKind {
+    Cancelled,
+    Timeout,
+}
 
 impl JoinError {
     /// Returns true if the error was caused by the task being cancelled.
     pub fn is_cancelled(&self) -> bool {
-        true
+        self.kind == JoinErrorKind::Cancelled
+    }
+
+    /// Returns true if the error was caused by the task exceeding its deadline.
+    ///
+    /// See [`spawn_with_deadline`].
+    #[cfg(feature = "sleep")]
+    pub fn is_timeout(&self) -> bool {
+        self.kind == JoinErrorKind::Timeout
     }
 }
 
@@ -43,7 +423,6 @@ impl JoinError {
 /// on it.
 ///
 /// This `struct` is created by the [`spawn`] function.
-#[derive(Debug)]
 pub struct JoinHandle<T> {
     state: Rc<Mutex<State<T>>>,
 }
@@ -61,9 +440,11 @@ impl<T> JoinHandle<T> {
     /// already completed at the time it was cancelled, but most likely it
     /// will fail with a [cancelled] [`JoinError`].
     ///
+    /// Task's future is dropped without being polled again.
+    ///
     /// [cancelled]: method@crate::spawn::JoinError::is_cancelled
     pub fn abort(&self) {
-        self.state.lock().unwrap().set_result(Err(JoinError {}));
+        self.state.abort();
     }
 
     /// Checks if the task associated with this `JoinHandle` has finished.
@@ -77,22 +458,24 @@ impl<T> JoinHandle<T> {
     pub fn is_finished(&self) -> bool {
         self.state.lock().unwrap().is_finished()
     }
+}
 
-    fn set_result(&self, value: T) {
-        self.state.lock().unwrap().set_result(Ok(value));
-    }
-
-    fn clone(&self) -> Self {
-        JoinHandle {
-            state: self.state.clone(),
-        }
+impl<T> Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("JoinHandle")
+            .field("finished", &state.is_finished())
+            .field("cancelled", &state.is_cancelled())
+            .finish()
     }
 }
 
-#[derive(Debug)]
 struct State<T> {
     result: Option<Result<T, JoinError>>,
     waker: Option<Waker>,
+    task_waker: Option<Waker>,
+    finished: bool,
+    cancelled: bool,
 }
 
 impl<T> State<T> {
@@ -100,17 +483,29 @@ impl<T> State<T> {
         State {
             result: None,
             waker: None,
+            task_waker: None,
+            finished: false,
+            cancelled: false,
         }
     }
 
     fn is_finished(&self) -> bool {
-        self.result.is_some()
+        self.finished
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
     }
 
     fn set_result(&mut self, value: Result<T, JoinError>) {
-        if self.result.is_none() {
+        if !self.finished {
+            self.finished = true;
+            self.cancelled = value.is_err();
             self.result = Some(value);
             self.wake();
+            if let Some(task_waker) = self.task_waker.take() {
+                task_waker.wake();
+            }
         }
     }
 
@@ -121,13 +516,21 @@ impl<T> State<T> {
     }
 
     fn update_waker(&mut self, waker: &Waker) {
-        if let Some(current_waker) = &self.waker {
-            if !waker.will_wake(current_waker) {
-                self.waker = Some(waker.clone());
-            }
-        } else {
-            self.waker = Some(waker.clone())
+        update_waker(&mut self.waker, waker);
+    }
+
+    fn update_task_waker(&mut self, waker: &Waker) {
+        update_waker(&mut self.task_waker, waker);
+    }
+}
+
+fn update_waker(current: &mut Option<Waker>, waker: &Waker) {
+    if let Some(current_waker) = current {
+        if !waker.will_wake(current_waker) {
+            *current = Some(waker.clone());
         }
+    } else {
+        *current = Some(waker.clone())
     }
 }
 
@@ -148,13 +551,251 @@ impl<T> Future for JoinHandle<T> {
     }
 }
 
+/// Handle used by task spawned with [`spawn_stream`] to yield values.
+#[cfg(feature = "queue")]
+pub struct Yielder<T> {
+    queue: Rc<Queue<T>>,
+}
+
+#[cfg(feature = "queue")]
+impl<T> Yielder<T> {
+    /// Yields `value` to the consumer, waiting until it's consumed.
+    pub async fn yield_value(&self, value: T) {
+        if self.queue.push_backpressured(value).await.is_ok() {
+            self.queue.wait_until_empty().await;
+        }
+    }
+}
+
+/// Stream returned by [`spawn_stream`].
+#[cfg(feature = "queue")]
+pub struct SpawnedStream<T> {
+    queue: Rc<Queue<T>>,
+    items: LocalBoxStream<'static, T>,
+    handle: JoinHandle<()>,
+}
+
+#[cfg(feature = "queue")]
+impl<T> Stream for SpawnedStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.items.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(feature = "queue")]
+impl<T> Drop for SpawnedStream<T> {
+    fn drop(&mut self) {
+        self.handle.abort();
+        self.queue.close();
+    }
+}
+
+/// Declares new task-local key of type [`TaskLocal`].
+///
+/// ```ignore
+/// task_local! {
+///     static TRACE_ID: u32;
+/// }
+///
+/// TRACE_ID.scope(42, async {
+///     assert_eq!(TRACE_ID.get(), 42);
+/// }).await;
+/// ```
+#[macro_export]
+macro_rules! task_local {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty;)+) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::spawn::TaskLocal<$t> = {
+                ::std::thread_local! {
+                    static KEY: ::std::cell::RefCell<::std::option::Option<$t>> =
+                        const { ::std::cell::RefCell::new(::std::option::Option::None) };
+                }
+                $crate::spawn::TaskLocal::__new(&KEY)
+            };
+        )+
+    };
+}
+
+/// Key for task-local data, declared with [`task_local!`] macro.
+///
+/// Value is set for the duration of a future with [`scope`] and
+/// can be accessed by any code polled within that future.
+///
+/// [`task_local!`]: crate::task_local
+/// [`scope`]: TaskLocal::scope
+pub struct TaskLocal<T: 'static> {
+    key: &'static LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> TaskLocal<T> {
+    #[doc(hidden)]
+    pub const fn __new(key: &'static LocalKey<RefCell<Option<T>>>) -> Self {
+        TaskLocal { key }
+    }
+
+    /// Sets task-local to `value` while `future` is being polled.
+    pub fn scope<F: Future>(&'static self, value: T, future: F) -> TaskLocalFuture<T, F> {
+        TaskLocalFuture {
+            local: self,
+            slot: Some(value),
+            future: Box::pin(future),
+        }
+    }
+
+    /// Accesses current value of the task-local.
+    ///
+    /// Must be called within [`scope`] - it'll panic otherwise.
+    ///
+    /// Value stays borrowed while `f` runs, so `f` must not poll another
+    /// [`scope`] of the same task-local - it'll panic as well.
+    ///
+    /// [`scope`]: TaskLocal::scope
+    pub fn with<F: FnOnce(&T) -> R, R>(&'static self, f: F) -> R {
+        self.try_with(f)
+            .expect("task-local value accessed outside of its scope")
+    }
+
+    /// Accesses current value of the task-local.
+    ///
+    /// Returns `None` if called outside of [`scope`].
+    ///
+    /// Same as with [`with`], `f` must not poll another [`scope`]
+    /// of the same task-local.
+    ///
+    /// [`scope`]: TaskLocal::scope
+    /// [`with`]: TaskLocal::with
+    pub fn try_with<F: FnOnce(&T) -> R, R>(&'static self, f: F) -> Option<R> {
+        self.key.with(|cell| cell.borrow().as_ref().map(f))
+    }
+}
+
+impl<T: Clone + 'static> TaskLocal<T> {
+    /// Returns copy of current value of the task-local.
+    ///
+    /// Must be called within [`scope`] - it'll panic otherwise.
+    ///
+    /// [`scope`]: TaskLocal::scope
+    pub fn get(&'static self) -> T {
+        self.with(T::clone)
+    }
+}
+
+impl<T: 'static> Debug for TaskLocal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskLocal").finish_non_exhaustive()
+    }
+}
+
+/// Future returned by [`TaskLocal::scope`].
+pub struct TaskLocalFuture<T: 'static, F> {
+    local: &'static TaskLocal<T>,
+    slot: Option<T>,
+    future: Pin<Box<F>>,
+}
+
+impl<T: 'static, F: Future> Future for TaskLocalFuture<T, F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let _guard = ScopeGuard::enter(this.local, &mut this.slot);
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// Keeps task-local value set while alive - restores previous
+/// value on drop, even if polled future panicked.
+struct ScopeGuard<'a, T: 'static> {
+    local: &'static TaskLocal<T>,
+    slot: &'a mut Option<T>,
+}
+
+impl<'a, T: 'static> ScopeGuard<'a, T> {
+    fn enter(local: &'static TaskLocal<T>, slot: &'a mut Option<T>) -> Self {
+        let mut guard = ScopeGuard { local, slot };
+        guard.swap();
+        guard
+    }
+
+    fn swap(&mut self) {
+        let slot = &mut *self.slot;
+        self.local
+            .key
+            .with(|cell| std::mem::swap(&mut *cell.borrow_mut(), slot));
+    }
+}
+
+impl<'a, T: 'static> Drop for ScopeGuard<'a, T> {
+    fn drop(&mut self) {
+        self.swap();
+    }
+}
+
+impl<T: 'static, F> Unpin for TaskLocalFuture<T, F> {}
+
+/// Polls `future` once with a no-op waker.
+///
+/// Meant for testing pure-logic futures synchronously,
+/// without a browser executor.
+#[cfg(feature = "dev")]
+pub fn poll_once<F>(future: &mut F) -> Poll<F::Output>
+where
+    F: Future + Unpin,
+{
+    let waker = futures::task::noop_waker();
+    Pin::new(future).poll(&mut Context::from_waker(&waker))
+}
+
+/// Polls `future` until it completes or stalls (returns [`Poll::Pending`]
+/// without waking itself).
+///
+/// Meant for testing pure-logic futures synchronously,
+/// without a browser executor.
+#[cfg(feature = "dev")]
+pub fn run_until_stalled<F>(future: &mut F) -> Poll<F::Output>
+where
+    F: Future + Unpin,
+{
+    let flag = Arc::new(WakeFlag::default());
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        flag.0.store(false, Ordering::SeqCst);
+        match Pin::new(&mut *future).poll(&mut cx) {
+            Poll::Ready(value) => return Poll::Ready(value),
+            Poll::Pending if !flag.0.load(Ordering::SeqCst) => return Poll::Pending,
+            Poll::Pending => {}
+        }
+    }
+}
+
+#[cfg(feature = "dev")]
+#[derive(Default)]
+struct WakeFlag(AtomicBool);
+
+#[cfg(feature = "dev")]
+impl Wake for WakeFlag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{cell::Cell, rc::Rc, time::Duration};
 
+    use futures::StreamExt;
     use wasm_bindgen_test::wasm_bindgen_test;
 
-    use crate::{sleep, spawn};
+    use crate::{
+        sleep, spawn,
+        spawn::{
+            runtime, spawn_batch, spawn_stream, spawn_then, spawn_with_cleanup, spawn_with_deadline,
+        },
+    };
 
     #[wasm_bindgen_test]
     async fn test_spawn() {
@@ -170,6 +811,122 @@ mod tests {
         assert_eq!(task_2.await.unwrap(), 2);
     }
 
+    crate::task_local! {
+        static TRACE_ID: u32;
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_task_local() {
+        assert_eq!(TRACE_ID.try_with(|id| *id), None);
+
+        let task = |id| {
+            spawn(TRACE_ID.scope(id, async move {
+                sleep(Duration::from_millis(50)).await;
+                let outer = TRACE_ID.get();
+                let inner = TRACE_ID.scope(id * 10, async { TRACE_ID.get() }).await;
+                sleep(Duration::from_millis(50)).await;
+                (outer, inner, TRACE_ID.get())
+            }))
+        };
+        let (task_1, task_2) = (task(1), task(2));
+        assert_eq!(task_1.await.unwrap(), (1, 10, 1));
+        assert_eq!(task_2.await.unwrap(), (2, 20, 2));
+        assert_eq!(TRACE_ID.try_with(|id| *id), None);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_spawn_with_cleanup() {
+        let cleaned = Rc::new(Cell::new(0));
+
+        let cleaned_clone = cleaned.clone();
+        let task = spawn_with_cleanup(async { 1 }, move || async move {
+            cleaned_clone.set(cleaned_clone.get() + 1)
+        });
+        assert_eq!(task.await.unwrap(), 1);
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(cleaned.get(), 0);
+
+        let cleaned_clone = cleaned.clone();
+        let task = spawn_with_cleanup(sleep(Duration::from_secs(10)), move || async move {
+            sleep(Duration::from_millis(10)).await;
+            cleaned_clone.set(cleaned_clone.get() + 1)
+        });
+        sleep(Duration::from_millis(100)).await;
+        task.abort();
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(cleaned.get(), 1);
+
+        let cleaned_clone = cleaned.clone();
+        let task = spawn_with_cleanup(sleep(Duration::from_secs(10)), move || async move {
+            cleaned_clone.set(cleaned_clone.get() + 1)
+        });
+        task.abort();
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(cleaned.get(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_spawn_then() {
+        let result = Rc::new(Cell::new(None));
+        let result_clone = result.clone();
+        spawn_then(async { 42 }, move |value| {
+            result_clone.set(Some(value.unwrap()))
+        });
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(result.get(), Some(42));
+
+        let cancelled = Rc::new(Cell::new(false));
+        let cancelled_clone = cancelled.clone();
+        let runtime = runtime();
+        runtime.spawn_then(sleep(Duration::from_secs(10)), move |value| {
+            cancelled_clone.set(value.unwrap_err().is_cancelled())
+        });
+        runtime.abort_all();
+        sleep(Duration::from_millis(100)).await;
+        assert!(cancelled.get());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_spawn_batch() {
+        let handles = spawn_batch(
+            (0..100)
+                .map(|i| async move {
+                    sleep(Duration::from_millis(10)).await;
+                    i * 2
+                })
+                .collect(),
+        );
+        handles[1].abort();
+
+        let mut results = vec![];
+        for handle in handles {
+            results.push(handle.await.ok());
+        }
+        assert_eq!(results[0], Some(0));
+        assert_eq!(results[1], None);
+        assert_eq!(results[99], Some(198));
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_spawn_stream() {
+        let produced = Rc::new(Cell::new(0));
+        let produced_clone = produced.clone();
+        let mut values = spawn_stream(move |yielder| async move {
+            for i in 1..=3 {
+                produced_clone.set(i);
+                yielder.yield_value(i).await;
+            }
+        });
+
+        assert_eq!(values.next().await, Some(1));
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(produced.get(), 2);
+
+        assert_eq!(values.next().await, Some(2));
+        assert_eq!(values.next().await, Some(3));
+        assert_eq!(values.next().await, None);
+    }
+
     #[wasm_bindgen_test]
     async fn test_abort() {
         let task = spawn(async {
@@ -179,5 +936,135 @@ mod tests {
         task.abort();
 
         assert!(task.await.unwrap_err().is_cancelled());
+
+        let done = Rc::new(Cell::new(false));
+        let done_clone = done.clone();
+        let task = spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            done_clone.set(true);
+        });
+        sleep(Duration::from_millis(10)).await;
+        task.abort();
+        sleep(Duration::from_millis(300)).await;
+        assert!(!done.get());
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_spawn_with_deadline() {
+        let task = spawn_with_deadline(Duration::from_millis(100), async {
+            sleep(Duration::from_secs(10)).await;
+            1
+        });
+        let error = task.await.unwrap_err();
+        assert!(error.is_timeout());
+        assert!(!error.is_cancelled());
+
+        let task = spawn_with_deadline(Duration::from_secs(10), async { 1 });
+        assert_eq!(task.await.unwrap(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_debug() {
+        let task = spawn(async {
+            sleep(Duration::from_secs(10)).await;
+            1
+        });
+        assert_eq!(
+            format!("{:?}", task),
+            "JoinHandle { finished: false, cancelled: false }"
+        );
+
+        task.abort();
+        assert_eq!(
+            format!("{:?}", task),
+            "JoinHandle { finished: true, cancelled: true }"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_runtime() {
+        let runtime = runtime();
+        assert_eq!(runtime.active_count(), 0);
+
+        let finished = runtime.spawn(async { 1 });
+        let task_1 = runtime.spawn(async {
+            sleep(Duration::from_secs(10)).await;
+            1
+        });
+        let task_2 = runtime.spawn(async {
+            sleep(Duration::from_secs(10)).await;
+            2
+        });
+        let other = spawn(async {
+            sleep(Duration::from_secs(1)).await;
+            3
+        });
+        assert_eq!(finished.await.unwrap(), 1);
+        assert_eq!(runtime.active_count(), 2);
+
+        runtime.abort_all();
+        assert_eq!(runtime.active_count(), 0);
+        assert!(task_1.await.unwrap_err().is_cancelled());
+        assert!(task_2.await.unwrap_err().is_cancelled());
+        assert_eq!(other.await.unwrap(), 3);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_runtime_prunes_finished_tasks() {
+        let runtime = runtime();
+        for i in 0..1000 {
+            assert_eq!(runtime.spawn(async move { i }).await.unwrap(), i);
+        }
+        assert!(runtime.tasks.borrow().len() < 100);
+        assert_eq!(runtime.active_count(), 0);
+    }
+
+    /// Plain tests run on host target - see `test.sh`.
+    #[cfg(not(target_arch = "wasm32"))]
+    mod host {
+        use std::task::Context;
+        #[cfg(feature = "dev")]
+        use std::task::Poll;
+
+        #[cfg(feature = "dev")]
+        use futures::future::{pending, poll_fn, ready};
+        use futures::FutureExt;
+
+        use super::TRACE_ID;
+        #[cfg(feature = "dev")]
+        use crate::spawn::{poll_once, run_until_stalled};
+
+        #[test]
+        fn test_task_local_panic() {
+            let mut future = TRACE_ID.scope(1, async { panic!("task panicked") });
+            let waker = futures::task::noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let _ = future.poll_unpin(&mut cx);
+            }));
+            assert!(result.is_err());
+            assert_eq!(TRACE_ID.try_with(|id| *id), None);
+        }
+
+        #[cfg(feature = "dev")]
+        #[test]
+        fn test_run_until_stalled() {
+            assert_eq!(poll_once(&mut ready(1)), Poll::Ready(1));
+            assert_eq!(poll_once(&mut pending::<i32>()), Poll::Pending);
+
+            let mut count = 0;
+            let mut yielding = poll_fn(|cx| {
+                if count < 2 {
+                    count += 1;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                } else {
+                    Poll::Ready(2)
+                }
+            });
+            assert_eq!(poll_once(&mut yielding), Poll::Pending);
+            assert_eq!(run_until_stalled(&mut yielding), Poll::Ready(2));
+            assert_eq!(run_until_stalled(&mut pending::<i32>()), Poll::Pending);
+        }
     }
 }
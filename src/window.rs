@@ -0,0 +1,255 @@
+//! Window-related utilities.
+
+#[cfg(feature = "event")]
+use std::cell::RefCell;
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use wasm_bindgen::{prelude::Closure, JsCast};
+#[cfg(feature = "event")]
+use web_sys::{BeforeUnloadEvent, HashChangeEvent, Window};
+
+#[cfg(feature = "event")]
+use crate::event::{EventListener, EventStream, Stream, When};
+use crate::{window, JsError};
+
+/// Create stream of window's `hashchange` events.
+///
+/// Useful for hash-based routing.
+#[cfg(feature = "event")]
+pub fn on_hashchange() -> Result<EventStream<Window, HashChangeEvent>, JsError> {
+    Rc::new(window()).listen("hashchange")
+}
+
+/// Guard making browser ask user for confirmation before leaving the page
+/// (e.g. to warn about unsaved changes) while it's alive.
+///
+/// Created with [`before_unload_guard`].
+#[cfg(feature = "event")]
+#[derive(Debug)]
+pub struct BeforeUnloadGuard {
+    _listener: EventListener<Window, BeforeUnloadEvent>,
+}
+
+/// Creates [`BeforeUnloadGuard`].
+#[cfg(feature = "event")]
+pub fn before_unload_guard() -> Result<BeforeUnloadGuard, JsError> {
+    let listener = Rc::new(window()).when("beforeunload", |event: BeforeUnloadEvent| {
+        // Modern browsers require `preventDefault`,
+        // older ones a non-empty `returnValue`
+        event.prevent_default();
+        event.set_return_value("unsaved changes");
+    })?;
+    Ok(BeforeUnloadGuard {
+        _listener: listener,
+    })
+}
+
+#[cfg(feature = "event")]
+thread_local! {
+    static BEFORE_UNLOAD_GUARD: RefCell<Option<BeforeUnloadGuard>> = const { RefCell::new(None) };
+}
+
+/// Enables or disables browser's confirmation prompt shown before leaving the page.
+///
+/// Global alternative to [`BeforeUnloadGuard`].
+#[cfg(feature = "event")]
+pub fn set_before_unload_prompt(enabled: bool) -> Result<(), JsError> {
+    let guard = if enabled {
+        if BEFORE_UNLOAD_GUARD.with(|guard| guard.borrow().is_some()) {
+            return Ok(());
+        }
+        Some(before_unload_guard()?)
+    } else {
+        None
+    };
+    BEFORE_UNLOAD_GUARD.with(|current| *current.borrow_mut() = guard);
+    Ok(())
+}
+
+/// Wraps `callback` so it's invoked once `duration` has passed since
+/// the last [`call`].
+///
+/// Imperative (`setTimeout`-based) debounce for non-async code.
+///
+/// [`call`]: DebouncedCallback::call
+pub fn debounced<F: FnMut() + 'static>(duration: Duration, mut callback: F) -> DebouncedCallback {
+    let handle = Rc::new(Cell::new(None));
+    let handle_clone = handle.clone();
+    let closure = Closure::<dyn FnMut()>::new(move || {
+        handle_clone.set(None);
+        callback();
+    });
+    DebouncedCallback {
+        duration,
+        handle,
+        closure,
+    }
+}
+
+/// Debounced callback created with [`debounced`].
+///
+/// Pending call is cancelled on drop.
+pub struct DebouncedCallback {
+    duration: Duration,
+    handle: Rc<Cell<Option<i32>>>,
+    closure: Closure<dyn FnMut()>,
+}
+
+impl DebouncedCallback {
+    /// (Re)arms the timer, cancelling previously pending call.
+    pub fn call(&self) -> Result<(), JsError> {
+        self.cancel();
+        let handle = window().set_timeout_with_callback_and_timeout_and_arguments_0(
+            self.closure.as_ref().unchecked_ref(),
+            self.duration.as_millis().try_into().unwrap_or(i32::MAX),
+        )?;
+        self.handle.set(Some(handle));
+        Ok(())
+    }
+
+    /// Cancels pending call.
+    pub fn cancel(&self) {
+        if let Some(handle) = self.handle.take() {
+            window().clear_timeout_with_handle(handle);
+        }
+    }
+
+    /// Returns `true` if call is pending.
+    pub fn is_pending(&self) -> bool {
+        self.handle.get().is_some()
+    }
+}
+
+impl Drop for DebouncedCallback {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Animation frame callback requested on demand.
+///
+/// Shared by animation frame based utilities, pending request is
+/// cancelled on drop.
+#[cfg(any(feature = "event", feature = "frame"))]
+#[derive(Debug)]
+pub(crate) struct FrameRequest {
+    handle: Rc<Cell<Option<i32>>>,
+    closure: Closure<dyn FnMut(f64)>,
+}
+
+#[cfg(any(feature = "event", feature = "frame"))]
+impl FrameRequest {
+    /// Wraps `callback` invoked with frame's timestamp.
+    pub(crate) fn new<F: FnMut(f64) + 'static>(mut callback: F) -> Self {
+        let handle = Rc::new(Cell::new(None));
+        let handle_clone = handle.clone();
+        let closure = Closure::<dyn FnMut(f64)>::new(move |time| {
+            handle_clone.set(None);
+            callback(time);
+        });
+        FrameRequest { handle, closure }
+    }
+
+    /// Requests callback to run on the next animation frame,
+    /// unless it's already requested.
+    pub(crate) fn request(&self) {
+        if self.handle.get().is_none() {
+            let closure = self.closure.as_ref().unchecked_ref();
+            self.handle
+                .set(window().request_animation_frame(closure).ok());
+        }
+    }
+}
+
+#[cfg(any(feature = "event", feature = "frame"))]
+impl Drop for FrameRequest {
+    fn drop(&mut self) {
+        if let (Some(handle), Some(window)) = (self.handle.take(), web_sys::window()) {
+            let _ = window.cancel_animation_frame(handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "sleep")]
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    #[cfg(feature = "event")]
+    use futures::StreamExt;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    #[cfg(feature = "event")]
+    use web_sys::{Event, EventInit};
+
+    #[cfg(feature = "event")]
+    use crate::{
+        location::set_hash,
+        window,
+        window::{before_unload_guard, on_hashchange, set_before_unload_prompt},
+    };
+    #[cfg(feature = "sleep")]
+    use crate::{sleep, window::debounced};
+
+    #[cfg(feature = "event")]
+    #[wasm_bindgen_test]
+    async fn test_on_hashchange() {
+        let mut stream = on_hashchange().unwrap();
+        // Hash might already be "#page" (or "#start") - change it twice
+        // and skip event of the first change if there's one.
+        set_hash("start").unwrap();
+        set_hash("page").unwrap();
+        loop {
+            let event = stream.next().await.unwrap();
+            if event.new_url().ends_with("#page") {
+                break;
+            }
+            assert!(event.new_url().ends_with("#start"));
+        }
+    }
+
+    #[cfg(feature = "event")]
+    #[wasm_bindgen_test]
+    fn test_before_unload() {
+        let dispatch = || {
+            let init = EventInit::new();
+            init.set_cancelable(true);
+            let event = Event::new_with_event_init_dict("beforeunload", &init).unwrap();
+            window().dispatch_event(&event).unwrap()
+        };
+
+        assert!(dispatch());
+        let guard = before_unload_guard().unwrap();
+        assert!(!dispatch());
+        drop(guard);
+        assert!(dispatch());
+
+        set_before_unload_prompt(true).unwrap();
+        set_before_unload_prompt(true).unwrap();
+        assert!(!dispatch());
+        set_before_unload_prompt(false).unwrap();
+        assert!(dispatch());
+    }
+
+    #[cfg(feature = "sleep")]
+    #[wasm_bindgen_test]
+    async fn test_debounced() {
+        let count = Rc::new(Cell::new(0));
+        let count_clone = count.clone();
+        let callback = debounced(Duration::from_millis(100), move || {
+            count_clone.set(count_clone.get() + 1)
+        });
+        callback.call().unwrap();
+        sleep(Duration::from_millis(50)).await;
+        callback.call().unwrap();
+        callback.call().unwrap();
+        assert!(callback.is_pending());
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(count.get(), 1);
+        assert!(!callback.is_pending());
+
+        callback.call().unwrap();
+        drop(callback);
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(count.get(), 1);
+    }
+}
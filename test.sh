@@ -2,3 +2,6 @@
 set -e
 
 wasm-pack test --chrome --headless
+
+# Tests in `tests::host` modules don't need a browser and run on host target
+cargo test --lib --features dev --target "$(rustc -vV | sed -n 's/^host: //p')" -- tests::host::